@@ -0,0 +1,12 @@
+//! Deserializes arbitrary values into typed targets, checking that it never
+//! panics and that every successfully deserialized target round trips.
+#![no_main]
+
+use decoder_value::{Value, fuzz};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|value: Value| {
+    if let Err(error) = fuzz::deserialize(&value) {
+        panic!("{error}");
+    }
+});
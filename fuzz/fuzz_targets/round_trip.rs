@@ -0,0 +1,12 @@
+//! Serializes and deserializes arbitrary values, checking that both produce the
+//! original value.
+#![no_main]
+
+use decoder_value::{Value, fuzz};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|value: Value| {
+    if let Err(error) = fuzz::round_trip(&value) {
+        panic!("{error}");
+    }
+});
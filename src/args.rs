@@ -0,0 +1,238 @@
+//! Load a [`Value`] from command-line arguments.
+//!
+//! Flags are nested by splitting their names with dots. Flags followed by a value
+//! become strings, bare flags become `true`, and repeated flags become sequences;
+//! decode repeatable flags with [`one_or_many`](crate::decode::one_or_many) to
+//! accept them when given once too.
+//! For instance, `--database.port=5432 --verbose --tag a --tag b` becomes
+//! `{ "database": { "port": "5432" }, "verbose": true, "tag": ["a", "b"] }`.
+//!
+//! ```rust
+//! use decoder::args::Args;
+//! use decoder::decode::{bool, lenient, map, one_or_many, string, u16};
+//!
+//! let args = Args::parse(["app", "--port", "8080", "--verbose", "--tag", "a", "--tag", "b"])
+//!     .expect("Parse arguments");
+//!
+//! let (port, verbose, tags): (u16, bool, Vec<String>) = args
+//!     .run(|value| {
+//!         let mut args = map(value)?;
+//!
+//!         Ok((
+//!             args.required("port", lenient(u16))?,
+//!             args.required("verbose", bool)?,
+//!             args.required("tag", one_or_many(string))?,
+//!         ))
+//!     })
+//!     .expect("Decode arguments");
+//!
+//! assert_eq!(port, 8080);
+//! assert!(verbose);
+//! assert_eq!(tags, ["a", "b"]);
+//! ```
+use crate::origin::{Conflict, Origins};
+use crate::value::Raw;
+use crate::{Decoder, Error, Result, Segment, Value};
+
+use indexmap::IndexMap;
+
+/// A [`Value`] loaded from command-line arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    value: Raw,
+    flags: Origins,
+}
+
+impl Args {
+    /// Parses the given command-line arguments.
+    ///
+    /// Arguments that do not belong to any flag (like the program name) are ignored,
+    /// and so is anything after a `--` argument. Since flags do not have a schema,
+    /// a flag followed by an argument that is not a flag always takes it as its value.
+    ///
+    /// Fails if a flag nests under another one (e.g. `--database` and
+    /// `--database.port`).
+    pub fn parse<T: Into<String>>(args: impl IntoIterator<Item = T>) -> Result<Self> {
+        let mut value = IndexMap::new();
+        let mut flags = Origins::default();
+
+        let mut args = args.into_iter().map(Into::into).peekable();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                break;
+            }
+
+            let Some(flag) = arg.strip_prefix("--").filter(|flag| !flag.is_empty()) else {
+                continue;
+            };
+
+            let (name, argument) = match flag.split_once('=') {
+                Some((name, argument)) => (name, Raw::String(argument.to_owned())),
+                None => match args.next_if(|arg| !arg.starts_with("--")) {
+                    Some(argument) => (flag, Raw::String(argument)),
+                    None => (flag, Raw::Bool(true)),
+                },
+            };
+
+            let keys: Vec<_> = name.split('.').map(str::to_owned).collect();
+
+            if keys.iter().any(String::is_empty) {
+                continue;
+            }
+
+            let flag = format!("--{name}");
+
+            flags
+                .insert(&mut value, &keys, argument, &flag, repeat)
+                .map_err(|Conflict(previous)| {
+                    Error::custom(format!("flag {flag} conflicts with {previous}"))
+                })?;
+        }
+
+        Ok(Self {
+            value: Raw::Map(value),
+            flags,
+        })
+    }
+
+    /// Returns the [`Value`] of the [`Args`].
+    pub fn value(&self) -> Value {
+        Value(self.value.clone())
+    }
+
+    /// Returns the flag that supplied the value at the given path, if any.
+    pub fn flag(&self, path: &[Segment]) -> Option<&str> {
+        self.flags.get(path)
+    }
+
+    /// Annotates the given [`Error`] with the flag that supplied the value that
+    /// caused it.
+    pub fn annotate(&self, error: Error) -> Error {
+        self.flags.annotate(error, describe)
+    }
+
+    /// Runs a [`Decoder`] on the [`Value`] of the [`Args`], annotating any [`Error`]
+    /// with the flag that supplied the invalid value.
+    pub fn run<T>(&self, decoder: impl Decoder<Output = T>) -> Result<T> {
+        self.flags.run(self.value(), decoder, describe)
+    }
+}
+
+/// Parses the given command-line arguments into a [`Value`].
+///
+/// See [`Args::parse`].
+pub fn parse<T: Into<String>>(args: impl IntoIterator<Item = T>) -> Result<Value> {
+    Args::parse(args).map(|args| args.value())
+}
+
+/// Collects the values of a repeated flag into a sequence.
+fn repeat(previous: &mut Raw, value: Raw) {
+    match previous {
+        Raw::Seq(sequence) => sequence.push(value),
+        previous => {
+            let first = std::mem::replace(previous, Raw::Unit);
+            *previous = Raw::Seq(vec![first, value]);
+        }
+    }
+}
+
+fn describe(flag: &str) -> String {
+    format!("flag {flag}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{bool, lenient, map, one_or_many, optional, string, u16};
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Options {
+        verbose: bool,
+        port: Option<u16>,
+        tags: Vec<String>,
+    }
+
+    impl Options {
+        fn decode(value: Value) -> Result<Self> {
+            let mut options = map(value)?;
+
+            Ok(Self {
+                verbose: options.optional("verbose", bool)?.unwrap_or(false),
+                port: options
+                    .optional("database", |value| {
+                        map(value)?.required("port", optional(lenient(u16)))
+                    })?
+                    .flatten(),
+                tags: options
+                    .optional("tag", one_or_many(string))?
+                    .unwrap_or_default(),
+            })
+        }
+    }
+
+    #[test]
+    fn it_parses_arguments() {
+        let args = Args::parse([
+            "app",
+            "--database.port=5432",
+            "--verbose",
+            "--tag",
+            "a",
+            "--tag",
+            "b",
+            "--",
+            "--ignored",
+        ])
+        .expect("Parse arguments");
+
+        assert_eq!(
+            args.run(Options::decode).expect("Decode options"),
+            Options {
+                verbose: true,
+                port: Some(5432),
+                tags: vec!["a".to_owned(), "b".to_owned()],
+            }
+        );
+    }
+
+    #[test]
+    fn it_decodes_repeatable_flags_given_once() {
+        let args = Args::parse(["app", "--tag", "a"]).expect("Parse arguments");
+
+        assert_eq!(
+            args.run(Options::decode).expect("Decode options").tags,
+            ["a"]
+        );
+    }
+
+    #[test]
+    fn it_mentions_flags_in_errors() {
+        let args = Args::parse(["app", "--database.port", "http"]).expect("Parse arguments");
+
+        let error = args.run(Options::decode).expect_err("Port is invalid");
+        assert!(error.to_string().contains("flag --database.port"));
+
+        let args = Args::parse(["app", "--verbose=yes"]).expect("Parse arguments");
+
+        let error = args.run(Options::decode).expect_err("Verbose is invalid");
+        assert!(error.to_string().contains("flag --verbose"));
+    }
+
+    #[test]
+    fn it_rejects_flags_nested_under_a_leaf() {
+        let error =
+            Args::parse(["app", "--database=x", "--database.port=1"]).expect_err("Flags conflict");
+        assert_eq!(
+            error.to_string(),
+            "custom error: flag --database.port conflicts with --database"
+        );
+
+        let error =
+            Args::parse(["app", "--database.port=1", "--database=x"]).expect_err("Flags conflict");
+        assert_eq!(
+            error.to_string(),
+            "custom error: flag --database conflicts with --database.port"
+        );
+    }
+}
@@ -0,0 +1,496 @@
+//! Read and write CSV data.
+//!
+//! Rows are read lazily from any [`Read`] implementor. By default, the first row
+//! contains the headers and every following row is presented as a [`Map`] with the
+//! headers as keys; otherwise, rows are presented as sequences. All the values are
+//! strings.
+//!
+//! ```rust
+//! use decoder::csv::Reader;
+//! use decoder::decode::{lenient, map, string, u32};
+//! use decoder::{Result, Value};
+//!
+//! struct Person {
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! impl Person {
+//!     fn decode(value: Value) -> Result<Self> {
+//!         let mut person = map(value)?;
+//!
+//!         Ok(Self {
+//!             name: person.required("name", string)?,
+//!             age: person.required("age", lenient(u32))?,
+//!         })
+//!     }
+//! }
+//!
+//! let input = "name,age\nHéctor,32\n\"Doe, Jane\",unknown\n";
+//! let mut people = Reader::new(input.as_bytes()).decode(Person::decode);
+//!
+//! assert_eq!(people.next().unwrap().unwrap().name, "Héctor");
+//! assert_eq!(
+//!     people.next().unwrap().err().unwrap().path(),
+//!     [decoder::Segment::Key("age".to_owned())]
+//! );
+//! ```
+use crate::value::{Raw, inner, scalar};
+use crate::{Decoder, Error, Map, Result, Value};
+
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// A reader of CSV rows.
+#[derive(Debug)]
+pub struct Reader<R> {
+    input: BufReader<R>,
+    delimiter: char,
+    has_headers: bool,
+    headers: Option<Vec<String>>,
+    row: usize,
+    is_done: bool,
+}
+
+/// An iterator that decodes each row of a [`Reader`] with a [`Decoder`].
+#[derive(Debug)]
+pub struct Decode<R, D> {
+    reader: Reader<R>,
+    decoder: D,
+}
+
+/// A writer of CSV rows.
+#[derive(Debug)]
+pub struct Writer<W> {
+    output: W,
+    delimiter: char,
+    headers: Option<Vec<String>>,
+}
+
+impl<R: Read> Reader<R> {
+    /// Creates a new [`Reader`] that uses the first row as headers.
+    pub fn new(input: R) -> Self {
+        Self {
+            input: BufReader::new(input),
+            delimiter: ',',
+            has_headers: true,
+            headers: None,
+            row: 0,
+            is_done: false,
+        }
+    }
+
+    /// Creates a new [`Reader`] without headers, which presents every row as a sequence.
+    pub fn without_headers(input: R) -> Self {
+        Self {
+            has_headers: false,
+            ..Self::new(input)
+        }
+    }
+
+    /// Sets the delimiter of the [`Reader`]; `,` by default.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Returns the headers of the [`Reader`], reading them if necessary.
+    ///
+    /// Fails if a header is repeated, since its columns would be presented under
+    /// the same key. In that case, no rows are read.
+    pub fn headers(&mut self) -> Result<&[String]> {
+        if self.has_headers && self.headers.is_none() && !self.is_done {
+            let headers = self.read_record()?.unwrap_or_default();
+            let mut seen = HashSet::new();
+
+            if let Some(header) = headers.iter().find(|header| !seen.insert(*header)) {
+                self.is_done = true;
+
+                return Err(Error::custom(format!("duplicate header `{header}`"))
+                    .origin(format!("row {}", self.row)));
+            }
+
+            self.headers = Some(headers);
+        }
+
+        Ok(self.headers.as_deref().unwrap_or_default())
+    }
+
+    /// Returns the number of the last row read, counting the headers.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Decodes every remaining row with the given [`Decoder`].
+    ///
+    /// Errors are annotated with the number of the row that caused them.
+    pub fn decode<D: Decoder>(self, decoder: D) -> Decode<R, D> {
+        Decode {
+            reader: self,
+            decoder,
+        }
+    }
+
+    fn next_row(&mut self) -> Result<Option<Value>> {
+        let _ = self.headers()?;
+
+        if self.is_done {
+            return Ok(None);
+        }
+
+        let Some(record) = self.read_record()? else {
+            return Ok(None);
+        };
+
+        let Some(headers) = &self.headers else {
+            return Ok(Some(Value(Raw::Seq(
+                record.into_iter().map(Raw::String).collect(),
+            ))));
+        };
+
+        if record.len() != headers.len() {
+            return Err(Error::InvalidLength {
+                expected: format!("{} fields", headers.len()).into(),
+                got: record.len(),
+            }
+            .origin(format!("row {}", self.row)));
+        }
+
+        Ok(Some(Value(Raw::Map(
+            headers
+                .iter()
+                .cloned()
+                .map(Raw::String)
+                .zip(record.into_iter().map(Raw::String))
+                .collect(),
+        ))))
+    }
+
+    fn read_record(&mut self) -> Result<Option<Vec<String>>> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+
+            if self
+                .input
+                .read_line(&mut line)
+                .map_err(Error::deserializer)?
+                == 0
+            {
+                return Ok(None);
+            }
+
+            if !line.trim_end_matches(['\r', '\n']).is_empty() {
+                break;
+            }
+        }
+
+        self.row += 1;
+
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+
+        loop {
+            let mut chars = line.chars().peekable();
+
+            while let Some(c) = chars.next() {
+                if quoted {
+                    if c != '"' {
+                        field.push(c);
+                    } else if chars.next_if_eq(&'"').is_some() {
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                } else if c == '"' && field.is_empty() {
+                    quoted = true;
+                } else if c == self.delimiter {
+                    fields.push(std::mem::take(&mut field));
+                } else if c != '\r' && c != '\n' {
+                    field.push(c);
+                }
+            }
+
+            if !quoted {
+                break;
+            }
+
+            line.clear();
+
+            if self
+                .input
+                .read_line(&mut line)
+                .map_err(Error::deserializer)?
+                == 0
+            {
+                return Err(
+                    Error::custom("unterminated quoted field").origin(format!("row {}", self.row))
+                );
+            }
+        }
+
+        fields.push(field);
+
+        Ok(Some(fields))
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_row().transpose()
+    }
+}
+
+impl<R: Read, D: Decoder> Iterator for Decode<R, D> {
+    type Item = Result<D::Output>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.reader.next()?;
+
+        Some(row.and_then(|row| {
+            self.decoder
+                .run(row)
+                .map_err(|error| error.origin(format!("row {}", self.reader.row)))
+        }))
+    }
+}
+
+impl<W: Write> Writer<W> {
+    /// Creates a new [`Writer`].
+    ///
+    /// Unless explicit headers are provided, the keys of the first row
+    /// are used as headers.
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            delimiter: ',',
+            headers: None,
+        }
+    }
+
+    /// Sets the delimiter of the [`Writer`]; `,` by default.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets the headers of the [`Writer`], which determine the order of the columns.
+    pub fn headers<T: Into<String>>(mut self, headers: impl IntoIterator<Item = T>) -> Self {
+        self.headers = Some(headers.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Writes a row.
+    ///
+    /// Missing and `None` fields are written as empty values. Fields that are not
+    /// in the headers produce an [`Error`].
+    pub fn write(&mut self, row: &Map) -> Result<()> {
+        let headers = match &self.headers {
+            Some(headers) => headers,
+            None => {
+                let headers = row.raw.keys().map(scalar).collect::<Result<Vec<_>>>()?;
+                self.write_record(&headers)?;
+
+                self.headers.insert(headers)
+            }
+        };
+
+        let mut fields: IndexMap<String, &Raw> = headers
+            .iter()
+            .map(|header| (header.clone(), &Raw::Unit))
+            .collect();
+
+        for (key, value) in &row.raw {
+            let key = scalar(key)?;
+
+            match fields.get_mut(&key) {
+                Some(field) => *field = value,
+                None => return Err(Error::custom(format!("unknown column: {key}"))),
+            }
+        }
+
+        let record = fields
+            .values()
+            .map(|value| match inner(value) {
+                Raw::Unit | Raw::Option(None) => Ok(String::new()),
+                value => scalar(value),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.write_record(&record)
+    }
+
+    /// Returns the underlying output of the [`Writer`].
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+
+    fn write_record(&mut self, record: &[String]) -> Result<()> {
+        let mut line = String::new();
+
+        for (i, field) in record.iter().enumerate() {
+            if i > 0 {
+                line.push(self.delimiter);
+            }
+
+            if field.contains([self.delimiter, '"', '\n', '\r']) {
+                line.push('"');
+                line.push_str(&field.replace('"', "\"\""));
+                line.push('"');
+            } else {
+                line.push_str(field);
+            }
+        }
+
+        line.push('\n');
+
+        self.output
+            .write_all(line.as_bytes())
+            .map_err(Error::deserializer)
+    }
+}
+
+/// Writes the given rows as CSV into a [`String`].
+///
+/// The keys of the first row are used as headers.
+pub fn to_string<'a>(rows: impl IntoIterator<Item = &'a Map>) -> Result<String> {
+    let mut writer = Writer::new(Vec::new());
+
+    for row in rows {
+        writer.write(row)?;
+    }
+
+    String::from_utf8(writer.into_inner())
+        .map_err(|error| Error::deserializer(io::Error::new(io::ErrorKind::InvalidData, error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{lenient, map, sequence, str, string, u32};
+    use crate::encode;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Person {
+        name: String,
+        age: Option<u32>,
+    }
+
+    impl Person {
+        fn decode(value: Value) -> Result<Self> {
+            let mut person = map(value)?;
+
+            Ok(Self {
+                name: person.required("name", string)?,
+                age: person.required("age", age)?,
+            })
+        }
+
+        fn encode(&self) -> Map {
+            encode::map([
+                ("name", encode::string(&self.name)),
+                ("age", encode::optional(encode::u32, self.age)),
+            ])
+        }
+    }
+
+    fn age(value: Value) -> Result<Option<u32>> {
+        if str(&value)?.is_empty() {
+            return Ok(None);
+        }
+
+        lenient(u32).run(value).map(Some)
+    }
+
+    #[test]
+    fn it_rejects_duplicate_headers() {
+        let mut rows = Reader::new("name,age,name\na,1,b\n".as_bytes());
+
+        let error = rows.next().unwrap().expect_err("Headers are duplicated");
+        assert!(matches!(&error, Error::Origin { origin, .. } if origin == "row 1"));
+        assert!(error.to_string().contains("duplicate header `name`"));
+
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn it_reads_rows() {
+        let input = "name,age\r\n\"Ramón, Héctor\",32\n\n\"Multi\nline \"\"quoted\"\"\",\n";
+
+        let people = Reader::new(input.as_bytes())
+            .decode(Person::decode)
+            .collect::<Result<Vec<_>>>()
+            .expect("Decode people");
+
+        assert_eq!(
+            people,
+            [
+                Person {
+                    name: "Ramón, Héctor".to_owned(),
+                    age: Some(32),
+                },
+                Person {
+                    name: "Multi\nline \"quoted\"".to_owned(),
+                    age: None,
+                },
+            ]
+        );
+
+        let rows = Reader::without_headers("a;b\nc;d".as_bytes())
+            .delimiter(';')
+            .map(|row| sequence(string).run(row?))
+            .collect::<Result<Vec<Vec<String>>>>()
+            .expect("Decode rows");
+
+        assert_eq!(rows, [["a", "b"], ["c", "d"]]);
+    }
+
+    #[test]
+    fn it_reports_rows_and_columns() {
+        let input = "name,age\nHéctor,32\nJane,old\nJohn\n";
+
+        let people: Vec<_> = Reader::new(input.as_bytes())
+            .decode(Person::decode)
+            .collect();
+
+        assert!(people[0].is_ok());
+
+        let error = people[1].as_ref().expect_err("Age is invalid");
+        assert!(matches!(error, Error::Origin { origin, .. } if origin == "row 3"));
+        assert_eq!(error.path(), [crate::Segment::Key("age".to_owned())]);
+
+        let error = people[2].as_ref().expect_err("Row is incomplete");
+        assert!(matches!(error, Error::Origin { origin, .. } if origin == "row 4"));
+    }
+
+    #[test]
+    fn it_writes_rows() {
+        let people = [
+            Person {
+                name: "Ramón, Héctor".to_owned(),
+                age: Some(32),
+            },
+            Person {
+                name: "Jane".to_owned(),
+                age: None,
+            },
+        ];
+
+        let rows: Vec<_> = people.iter().map(Person::encode).collect();
+        let output = to_string(&rows).expect("Write rows");
+
+        assert_eq!(output, "name,age\n\"Ramón, Héctor\",32\nJane,\n");
+
+        let decoded = Reader::new(output.as_bytes())
+            .decode(Person::decode)
+            .collect::<Result<Vec<_>>>()
+            .expect("Decode people");
+
+        assert_eq!(decoded, people);
+    }
+}
@@ -1,9 +1,18 @@
 //! Decode your types.
 use crate::value::Raw;
-use crate::{Decoder, Error, Map, Result, Value};
+use crate::{Decoder, Error, Map, Result, Unexpected, Value};
 
 use serde::de::{DeserializeOwned, IntoDeserializer};
-use std::time::Duration;
+use std::ffi::OsString;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64,
+};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 /// Decodes a [`bool`](prim@bool).
 pub fn bool(value: Value) -> Result<bool> {
@@ -60,6 +69,70 @@ pub fn f64(value: Value) -> Result<f64> {
     deserialize(value)
 }
 
+/// Decodes a [`NonZeroU8`].
+pub fn non_zero_u8(value: Value) -> Result<NonZeroU8> {
+    NonZeroU8::new(u8(value)?).ok_or_else(|| zero(Unexpected::Unsigned(0)))
+}
+
+/// Decodes a [`NonZeroU16`].
+pub fn non_zero_u16(value: Value) -> Result<NonZeroU16> {
+    NonZeroU16::new(u16(value)?).ok_or_else(|| zero(Unexpected::Unsigned(0)))
+}
+
+/// Decodes a [`NonZeroU32`].
+pub fn non_zero_u32(value: Value) -> Result<NonZeroU32> {
+    NonZeroU32::new(u32(value)?).ok_or_else(|| zero(Unexpected::Unsigned(0)))
+}
+
+/// Decodes a [`NonZeroU64`].
+pub fn non_zero_u64(value: Value) -> Result<NonZeroU64> {
+    NonZeroU64::new(u64(value)?).ok_or_else(|| zero(Unexpected::Unsigned(0)))
+}
+
+/// Decodes a [`NonZeroI8`].
+pub fn non_zero_i8(value: Value) -> Result<NonZeroI8> {
+    NonZeroI8::new(i8(value)?).ok_or_else(|| zero(Unexpected::Signed(0)))
+}
+
+/// Decodes a [`NonZeroI16`].
+pub fn non_zero_i16(value: Value) -> Result<NonZeroI16> {
+    NonZeroI16::new(i16(value)?).ok_or_else(|| zero(Unexpected::Signed(0)))
+}
+
+/// Decodes a [`NonZeroI32`].
+pub fn non_zero_i32(value: Value) -> Result<NonZeroI32> {
+    NonZeroI32::new(i32(value)?).ok_or_else(|| zero(Unexpected::Signed(0)))
+}
+
+/// Decodes a [`NonZeroI64`].
+pub fn non_zero_i64(value: Value) -> Result<NonZeroI64> {
+    NonZeroI64::new(i64(value)?).ok_or_else(|| zero(Unexpected::Signed(0)))
+}
+
+/// Decodes a [`char`](prim@char).
+///
+/// Strings containing a single character are also accepted.
+pub fn char(value: Value) -> Result<char> {
+    match value.0 {
+        Raw::Char(char) => Ok(char),
+        Raw::String(string) => {
+            let mut chars = string.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(char), None) => Ok(char),
+                _ => Err(Error::InvalidValue {
+                    expected: "a single character".into(),
+                    got: Unexpected::Str(string),
+                }),
+            }
+        }
+        value => Err(Error::InvalidType {
+            expected: "char".into(),
+            got: value.unexpected(),
+        }),
+    }
+}
+
 /// Decodes a [`String`].
 pub fn string(value: Value) -> Result<String> {
     deserialize(value)
@@ -70,18 +143,48 @@ pub fn str(value: &Value) -> Result<&str> {
     match &value.0 {
         Raw::String(string) => Ok(string),
         value => Err(Error::InvalidType {
-            expected: "string",
+            expected: "string".into(),
             got: value.unexpected(),
         }),
     }
 }
 
+/// Decodes a [`PathBuf`].
+pub fn path_buf(value: Value) -> Result<PathBuf> {
+    string(value).map(PathBuf::from)
+}
+
+/// Decodes an [`OsString`].
+pub fn os_string(value: Value) -> Result<OsString> {
+    string(value).map(OsString::from)
+}
+
+/// Decodes an [`IpAddr`] from its string representation.
+pub fn ip_addr(value: Value) -> Result<IpAddr> {
+    parse(value, "an IP address")
+}
+
+/// Decodes an [`Ipv4Addr`] from its string representation.
+pub fn ipv4_addr(value: Value) -> Result<Ipv4Addr> {
+    parse(value, "an IPv4 address")
+}
+
+/// Decodes an [`Ipv6Addr`] from its string representation.
+pub fn ipv6_addr(value: Value) -> Result<Ipv6Addr> {
+    parse(value, "an IPv6 address")
+}
+
+/// Decodes a [`SocketAddr`] from its string representation.
+pub fn socket_addr(value: Value) -> Result<SocketAddr> {
+    parse(value, "a socket address")
+}
+
 /// Decodes a [`Map`].
 pub fn map(value: Value) -> Result<Map> {
     match value.0 {
-        Raw::Map(map) => Ok(Map { raw: map }),
+        Raw::Map(map) => Ok(Map::from_raw(map)),
         value => Err(Error::InvalidType {
-            expected: "map",
+            expected: "map".into(),
             got: value.unexpected(),
         }),
     }
@@ -97,6 +200,157 @@ pub fn duration(value: Value) -> Result<Duration> {
     Ok(Duration::new(secs, nanos))
 }
 
+/// Decodes a [`Duration`] from a human-friendly representation.
+///
+/// The following representations are accepted:
+/// - A sequence of numbers followed by a unit (`ns`, `us`, `µs`, `ms`, `s`, `m`, `h`, or `d`),
+///   like `250ms`, `1m30s`, or `1.5h`.
+/// - An ISO 8601 duration with days, hours, minutes, and seconds, like `PT1M30S`.
+/// - A plain number of seconds, or a string containing one (like `"30"`). Use
+///   [`duration_human_in`] to choose a different unit.
+pub fn duration_human(value: Value) -> Result<Duration> {
+    duration_human_in(Duration::from_secs(1)).run(value)
+}
+
+/// Produces a [`Decoder`] like [`duration_human`], but plain numbers (and strings
+/// containing one) are interpreted in the given unit.
+pub fn duration_human_in(unit: Duration) -> impl Decoder<Output = Duration> {
+    const EXPECTED: &str = "a duration (e.g. 1m30s)";
+
+    move |value: Value| {
+        if let Raw::String(string) = &value.0 {
+            let string = string.trim();
+
+            return parse_decimal(string)
+                .and_then(|amount| nanos_to_duration(scale(amount, unit.as_nanos())?))
+                .or_else(|| parse_duration(string))
+                .or_else(|| parse_iso8601_duration(string))
+                .ok_or_else(|| Error::InvalidValue {
+                    expected: EXPECTED.into(),
+                    got: Unexpected::Str(string.to_owned()),
+                });
+        }
+
+        let unexpected = value.0.unexpected();
+        let amount = f64(value)?;
+
+        if amount.is_sign_negative() {
+            return Err(Error::InvalidValue {
+                expected: EXPECTED.into(),
+                got: unexpected,
+            });
+        }
+
+        Duration::try_from_secs_f64(unit.as_secs_f64() * amount).map_err(|_| Error::InvalidValue {
+            expected: EXPECTED.into(),
+            got: unexpected,
+        })
+    }
+}
+
+/// Decodes a number of bytes from a human-friendly representation.
+///
+/// Numbers may be followed by a decimal (`kB`, `MB`, `GB`, `TB`, `PB`) or
+/// binary (`KiB`, `MiB`, `GiB`, `TiB`, `PiB`) unit, like `512MiB` or `1.5GB`.
+/// Plain numbers and numbers followed by `B` are interpreted as bytes.
+pub fn byte_size(value: Value) -> Result<u64> {
+    match &value.0 {
+        Raw::String(string) => parse_byte_size(string.trim()).ok_or_else(|| Error::InvalidValue {
+            expected: "a byte size (e.g. 512MiB)".into(),
+            got: Unexpected::Str(string.clone()),
+        }),
+        _ => u64(value),
+    }
+}
+
+/// Decodes a [`SystemTime`] from the seconds and nanoseconds elapsed since the
+/// [`UNIX_EPOCH`].
+///
+/// See [`encode::system_time`](crate::encode::system_time).
+///
+/// [`UNIX_EPOCH`]: std::time::UNIX_EPOCH
+pub fn system_time(value: Value) -> Result<SystemTime> {
+    let mut time = map(value)?;
+
+    let secs = time.required("secs", |value: Value| match value.0 {
+        Raw::I8(_) | Raw::I16(_) | Raw::I32(_) | Raw::I64(_) => i64(value).map(i128::from),
+        _ => u64(value).map(i128::from),
+    })?;
+    let nanos = Duration::from_nanos(time.required("nanos", u32)?.into());
+
+    let elapsed = Duration::from_secs(secs.unsigned_abs() as u64);
+
+    let time = if secs < 0 {
+        SystemTime::UNIX_EPOCH.checked_sub(elapsed)
+    } else {
+        SystemTime::UNIX_EPOCH.checked_add(elapsed)
+    };
+
+    time.and_then(|time| time.checked_add(nanos))
+        .ok_or_else(|| {
+            Error::OutOfRange {
+                expected: "a system time within range".into(),
+                got: match i64::try_from(secs) {
+                    Ok(secs) if secs < 0 => Unexpected::Signed(secs),
+                    _ => Unexpected::Unsigned(secs as u64),
+                },
+            }
+            .field("secs")
+        })
+}
+
+/// Decodes an RFC 3339 date-time (e.g. `1979-05-27T07:32:00Z`).
+///
+/// TOML offset date-times are also accepted.
+#[cfg(feature = "chrono")]
+pub fn datetime(value: Value) -> Result<chrono::DateTime<chrono::FixedOffset>> {
+    parse_datetime(&value, "an RFC 3339 date-time", |string| {
+        chrono::DateTime::parse_from_rfc3339(string).ok()
+    })
+}
+
+/// Decodes a calendar date (e.g. `1979-05-27`).
+///
+/// TOML local dates are also accepted.
+#[cfg(feature = "chrono")]
+pub fn date(value: Value) -> Result<chrono::NaiveDate> {
+    parse_datetime(&value, "a date (e.g. 1979-05-27)", |string| {
+        chrono::NaiveDate::parse_from_str(string, "%Y-%m-%d").ok()
+    })
+}
+
+/// Decodes a time of day (e.g. `07:32:00` or `07:32:00.999`).
+///
+/// TOML local times are also accepted.
+#[cfg(feature = "chrono")]
+pub fn time(value: Value) -> Result<chrono::NaiveTime> {
+    parse_datetime(&value, "a time (e.g. 07:32:00)", |string| {
+        chrono::NaiveTime::parse_from_str(string, "%H:%M:%S%.f").ok()
+    })
+}
+
+/// Decodes a date-time from the number of seconds since the Unix epoch.
+#[cfg(feature = "chrono")]
+pub fn unix_timestamp(value: Value) -> Result<chrono::DateTime<chrono::Utc>> {
+    let seconds = i64(value)?;
+
+    chrono::DateTime::from_timestamp(seconds, 0).ok_or(Error::InvalidValue {
+        expected: "a Unix timestamp in seconds".into(),
+        got: Unexpected::Signed(seconds),
+    })
+}
+
+/// Decodes a date-time from the number of milliseconds since the Unix epoch.
+#[cfg(feature = "chrono")]
+pub fn unix_timestamp_millis(value: Value) -> Result<chrono::DateTime<chrono::Utc>> {
+    let millis = i64(value)?;
+
+    chrono::DateTime::from_timestamp_millis(millis).ok_or(Error::InvalidValue {
+        expected: "a Unix timestamp in milliseconds".into(),
+        got: Unexpected::Signed(millis),
+    })
+}
+
 /// Takes a [`Decoder`] of a certain type `T` and produces a new [`Decoder`] that
 /// decodes an `Option<T>`.
 pub fn optional<T>(decoder: impl Decoder<Output = T>) -> impl Decoder<Output = Option<T>> {
@@ -107,6 +361,31 @@ pub fn optional<T>(decoder: impl Decoder<Output = T>) -> impl Decoder<Output = O
     }
 }
 
+/// Takes a decoder of fields of a certain type `T` and produces a new [`Decoder`]
+/// that decodes a map into `T`, ignoring any remaining fields.
+///
+/// A decoder of fields can also be called with the [`Map`] of a parent to decode
+/// its fields flattened, which lets the same type be decoded both nested and
+/// flattened.
+pub fn fields<T>(decoder: impl Fn(&mut Map) -> Result<T>) -> impl Decoder<Output = T> {
+    move |value: Value| decoder(&mut map(value)?)
+}
+
+/// Takes a decoder of fields of a certain type `T` and produces a new [`Decoder`]
+/// that decodes a map into `T`, failing on any remaining fields.
+///
+/// See [`Map::finish`].
+pub fn strict<T>(decoder: impl Fn(&mut Map) -> Result<T>) -> impl Decoder<Output = T> {
+    move |value: Value| {
+        let mut map = map(value)?.strict();
+        let output = decoder(&mut map)?;
+
+        map.finish()?;
+
+        Ok(output)
+    }
+}
+
 /// Takes a [`Decoder`] of a certain type `T` and produces a new [`Decoder`] that
 /// decodes a sequence of `T` values.
 pub fn sequence<T, B: FromIterator<T>>(
@@ -116,15 +395,287 @@ pub fn sequence<T, B: FromIterator<T>>(
         Raw::Seq(sequence) => sequence
             .into_iter()
             .map(Value)
-            .map(|value| decoder.run(value))
+            .enumerate()
+            .map(|(index, value)| decoder.run(value).map_err(|error| error.item(index)))
             .collect(),
         value => Err(Error::InvalidType {
-            expected: "sequence",
+            expected: "sequence".into(),
             got: value.unexpected(),
         }),
     }
 }
 
+/// Takes a [`Decoder`] of a certain type `T` and produces a new [`Decoder`] that
+/// decodes a sequence of `T` values, or a single `T` value as a sequence of one.
+///
+/// This is useful when decoding repeatable flags or parameters, which only
+/// become a sequence once they are repeated.
+pub fn one_or_many<T, B: FromIterator<T>>(
+    decoder: impl Decoder<Output = T>,
+) -> impl Decoder<Output = B> {
+    let sequence = sequence(decoder);
+
+    move |value: Value| match value.0 {
+        Raw::Seq(_) => sequence.run(value),
+        _ => sequence.run(Value(Raw::Seq(vec![value.0]))),
+    }
+}
+
+/// Takes a [`Decoder`] of a certain type `T` and produces a new [`Decoder`] that
+/// decodes a `Box<T>`.
+pub fn boxed<T>(decoder: impl Decoder<Output = T>) -> impl Decoder<Output = Box<T>> {
+    move |value: Value| decoder.run(value).map(Box::new)
+}
+
+/// Takes a [`Decoder`] of a certain type `T` and produces a new [`Decoder`] that
+/// decodes an `Rc<T>`.
+pub fn rc<T>(decoder: impl Decoder<Output = T>) -> impl Decoder<Output = Rc<T>> {
+    move |value: Value| decoder.run(value).map(Rc::new)
+}
+
+/// Takes a [`Decoder`] of a certain type `T` and produces a new [`Decoder`] that
+/// decodes an `Arc<T>`.
+pub fn arc<T>(decoder: impl Decoder<Output = T>) -> impl Decoder<Output = Arc<T>> {
+    move |value: Value| decoder.run(value).map(Arc::new)
+}
+
+/// Takes a [`Decoder`] and produces a new [`Decoder`] that also accepts the string
+/// representation of booleans and numbers.
+///
+/// This is useful when decoding sources where every value is a string, like
+/// environment variables or query strings.
+pub fn lenient<T>(decoder: impl Decoder<Output = T>) -> impl Decoder<Output = T> {
+    move |value: Value| {
+        let Raw::String(string) = &value.0 else {
+            return decoder.run(value);
+        };
+
+        let parsed = if let Ok(bool) = string.parse() {
+            Raw::Bool(bool)
+        } else if let Ok(u64) = string.parse() {
+            Raw::U64(u64)
+        } else if let Ok(i64) = string.parse() {
+            Raw::I64(i64)
+        } else if let Ok(f64) = string.parse() {
+            Raw::F64(f64)
+        } else {
+            return decoder.run(value);
+        };
+
+        decoder
+            .run(Value(parsed))
+            .or_else(|error| decoder.run(value).map_err(|_| error))
+    }
+}
+
+fn parse<T: FromStr>(value: Value, expected: &'static str) -> Result<T> {
+    let string = str(&value)?;
+
+    string.parse().map_err(|_| Error::InvalidValue {
+        expected: expected.into(),
+        got: Unexpected::Str(string.to_owned()),
+    })
+}
+
+/// Parses a decimal number, returning its integer part and its fractional part
+/// as a numerator and a power of ten.
+fn parse_decimal(input: &str) -> Option<(u128, u128, u32)> {
+    let (integer, fraction) = input.split_once('.').unwrap_or((input, ""));
+
+    if integer.is_empty() && fraction.is_empty()
+        || !integer.bytes().all(|byte| byte.is_ascii_digit())
+        || !fraction.bytes().all(|byte| byte.is_ascii_digit())
+        || fraction.len() > 18
+    {
+        return None;
+    }
+
+    let parse = |digits: &str| {
+        if digits.is_empty() {
+            Some(0)
+        } else {
+            digits.parse::<u128>().ok()
+        }
+    };
+
+    Some((parse(integer)?, parse(fraction)?, fraction.len() as u32))
+}
+
+/// Multiplies a decimal number by the given amount of units, rounding down.
+fn scale(decimal: (u128, u128, u32), unit: u128) -> Option<u128> {
+    let (integer, numerator, digits) = decimal;
+
+    integer
+        .checked_mul(unit)?
+        .checked_add(numerator.checked_mul(unit)? / 10u128.pow(digits))
+}
+
+fn nanos_to_duration(nanos: u128) -> Option<Duration> {
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+fn parse_duration(input: &str) -> Option<Duration> {
+    const UNITS: &[(&str, u128)] = &[
+        ("ns", 1),
+        ("us", 1_000),
+        ("µs", 1_000),
+        ("ms", 1_000_000),
+        ("s", 1_000_000_000),
+        ("m", 60 * 1_000_000_000),
+        ("h", 60 * 60 * 1_000_000_000),
+        ("d", 24 * 60 * 60 * 1_000_000_000),
+    ];
+
+    let mut rest = input;
+    let mut nanos: u128 = 0;
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+
+        let number = parse_decimal(&rest[..end])?;
+        rest = &rest[end..];
+
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(rest.len());
+
+        let unit = UNITS
+            .iter()
+            .find(|(name, _)| *name == &rest[..end])
+            .map(|(_, unit)| *unit)?;
+
+        nanos = nanos.checked_add(scale(number, unit)?)?;
+        rest = rest[end..].trim_start();
+    }
+
+    nanos_to_duration(nanos)
+}
+
+fn parse_iso8601_duration(input: &str) -> Option<Duration> {
+    let input = input.strip_prefix(['P', 'p'])?;
+    let (date, time) = input.split_once(['T', 't']).unwrap_or((input, ""));
+
+    if date.is_empty() && time.is_empty() {
+        return None;
+    }
+
+    let mut nanos: u128 = 0;
+
+    for (part, units) in [
+        (
+            date,
+            &[
+                ('D', 24 * 60 * 60 * 1_000_000_000),
+                ('W', 7 * 24 * 60 * 60 * 1_000_000_000),
+            ][..],
+        ),
+        (
+            time,
+            &[
+                ('H', 60 * 60 * 1_000_000_000),
+                ('M', 60 * 1_000_000_000),
+                ('S', 1_000_000_000),
+            ][..],
+        ),
+    ] {
+        let mut rest = part;
+
+        while !rest.is_empty() {
+            let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+            let number = parse_decimal(&rest[..end].replace(',', "."))?;
+
+            let designator = rest[end..].chars().next()?.to_ascii_uppercase();
+            let unit = units
+                .iter()
+                .find(|(name, _)| *name == designator)
+                .map(|(_, unit)| *unit)?;
+
+            nanos = nanos.checked_add(scale(number, unit)?)?;
+            rest = &rest[end + 1..];
+        }
+    }
+
+    nanos_to_duration(nanos)
+}
+
+fn parse_byte_size(input: &str) -> Option<u64> {
+    const UNITS: &[(&str, u128)] = &[
+        ("", 1),
+        ("B", 1),
+        ("kB", 1_000),
+        ("KB", 1_000),
+        ("MB", 1_000_000),
+        ("GB", 1_000_000_000),
+        ("TB", 1_000_000_000_000),
+        ("PB", 1_000_000_000_000_000),
+        ("KiB", 1 << 10),
+        ("MiB", 1 << 20),
+        ("GiB", 1 << 30),
+        ("TiB", 1 << 40),
+        ("PiB", 1 << 50),
+    ];
+
+    let end = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+
+    let number = parse_decimal(&input[..end])?;
+
+    let unit = UNITS
+        .iter()
+        .find(|(name, _)| *name == input[end..].trim_start())
+        .map(|(_, unit)| *unit)?;
+
+    u64::try_from(scale(number, unit)?).ok()
+}
+
+/// Parses a date-time string, looking through the map used by the TOML format
+/// to represent its native date-times.
+#[cfg(feature = "chrono")]
+fn parse_datetime<T>(
+    value: &Value,
+    expected: &'static str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<T> {
+    let raw = match &value.0 {
+        Raw::Map(map) if map.len() == 1 => map
+            .get(&Raw::String(TOML_DATETIME.to_owned()))
+            .unwrap_or(&value.0),
+        raw => raw,
+    };
+
+    let Raw::String(string) = raw else {
+        return Err(Error::InvalidType {
+            expected: "string".into(),
+            got: raw.unexpected(),
+        });
+    };
+
+    parse(string).ok_or_else(|| Error::InvalidValue {
+        expected: expected.into(),
+        got: Unexpected::Str(string.to_owned()),
+    })
+}
+
+/// The key of the map used by the TOML format to represent its datetimes.
+#[cfg(feature = "chrono")]
+pub(crate) const TOML_DATETIME: &str = "$__toml_private_datetime";
+
+fn zero(got: Unexpected) -> Error {
+    Error::InvalidValue {
+        expected: "a non-zero number".into(),
+        got,
+    }
+}
+
 fn deserialize<T: DeserializeOwned>(value: Value) -> Result<T> {
     Ok(T::deserialize(value.into_deserializer())?)
 }
@@ -212,4 +763,434 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn it_decodes_std_types() {
+        use crate::encode;
+
+        use std::time::UNIX_EPOCH;
+
+        for time in [
+            UNIX_EPOCH + Duration::new(1_700_000_000, 42),
+            UNIX_EPOCH - Duration::new(1, 250_000_000),
+            UNIX_EPOCH - Duration::from_secs(86_400),
+        ] {
+            assert_eq!(system_time(encode::system_time(time)).unwrap(), time);
+        }
+
+        let error = system_time(
+            encode::map([("secs", encode::u64(u64::MAX)), ("nanos", encode::u32(0))]).into(),
+        )
+        .expect_err("Time is out of range");
+
+        assert_eq!(
+            error.root().to_string(),
+            "out of range (expected: a system time within range, got: Unsigned(18446744073709551615))"
+        );
+
+        let address: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        assert_eq!(socket_addr(encode::socket_addr(address)).unwrap(), address);
+        assert_eq!(
+            ip_addr(encode::string("::1")).unwrap(),
+            IpAddr::from(Ipv6Addr::LOCALHOST)
+        );
+
+        assert_eq!(
+            path_buf(encode::path("/etc/hosts")).unwrap(),
+            PathBuf::from("/etc/hosts")
+        );
+        assert_eq!(char(encode::string("é")).unwrap(), 'é');
+        assert_eq!(char(encode::char('x')).unwrap(), 'x');
+        assert_eq!(
+            non_zero_u16(encode::u16(443)).unwrap(),
+            NonZeroU16::new(443).unwrap()
+        );
+        assert_eq!(*arc(u8).run(encode::u8(7)).unwrap(), 7);
+    }
+
+    #[test]
+    fn it_rejects_invalid_std_values() {
+        use crate::encode;
+
+        assert!(matches!(
+            ipv4_addr(encode::string("::1")),
+            Err(Error::InvalidValue {
+                got: Unexpected::Str(address),
+                ..
+            }) if address == "::1"
+        ));
+        assert!(matches!(
+            ipv4_addr(encode::u32(1)),
+            Err(Error::InvalidType { .. })
+        ));
+        assert!(matches!(
+            char(encode::string("ab")),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            non_zero_i32(encode::i32(0)),
+            Err(Error::InvalidValue {
+                got: Unexpected::Signed(0),
+                ..
+            })
+        ));
+        assert!(matches!(
+            non_zero_u8(encode::u8(0)),
+            Err(Error::InvalidValue {
+                got: Unexpected::Unsigned(0),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn it_decodes_human_durations_and_byte_sizes() {
+        use crate::encode;
+
+        let duration = |input: &str| duration_human(encode::string(input)).ok();
+
+        assert_eq!(duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(duration("1h2m"), Some(Duration::from_secs(3720)));
+        assert_eq!(duration("1m 30.5s"), Some(Duration::from_millis(90_500)));
+        assert_eq!(duration("PT1M30S"), Some(Duration::from_secs(90)));
+        assert_eq!(
+            duration("P1DT0.5S"),
+            Some(Duration::from_millis(86_400_500))
+        );
+        assert_eq!(duration("1y"), None);
+        assert_eq!(duration("P1Y"), None);
+        assert_eq!(duration(""), None);
+
+        assert_eq!(
+            duration_human_in(Duration::from_millis(1))
+                .run(encode::u32(1500))
+                .unwrap(),
+            Duration::from_millis(1500)
+        );
+        assert!(duration_human(encode::i32(-1)).is_err());
+
+        assert_eq!(duration("30"), Some(Duration::from_secs(30)));
+        assert_eq!(
+            duration_human_in(Duration::from_millis(1))
+                .run(encode::string(" 1500 "))
+                .unwrap(),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(duration("-30"), None);
+
+        let size = |input: &str| byte_size(encode::string(input)).ok();
+
+        assert_eq!(size("512MiB"), Some(512 * 1024 * 1024));
+        assert_eq!(size("1.5 GB"), Some(1_500_000_000));
+        assert_eq!(size("42"), Some(42));
+        assert_eq!(size("1 XB"), None);
+        assert_eq!(byte_size(encode::u64(10)).unwrap(), 10);
+
+        for duration in [
+            Duration::ZERO,
+            Duration::from_millis(90_500),
+            Duration::new(93_784, 5),
+        ] {
+            assert_eq!(
+                duration_human(encode::duration_human(duration)).unwrap(),
+                duration
+            );
+        }
+
+        assert_eq!(
+            encode::duration_human(Duration::from_secs(90)),
+            encode::string("1m30s")
+        );
+        assert_eq!(
+            encode::byte_size(512 * 1024 * 1024),
+            encode::string("512MiB")
+        );
+        assert_eq!(encode::byte_size(2_000), encode::string("2kB"));
+        assert_eq!(encode::byte_size(1_500), encode::string("1500B"));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn it_decodes_dates_and_times() {
+        use crate::encode;
+
+        let datetime = super::datetime(encode::string("1979-05-27T07:32:00.5-07:00")).unwrap();
+        assert_eq!(datetime.timestamp_millis(), 296_663_520_500);
+        assert_eq!(
+            super::datetime(encode::datetime(datetime)).unwrap(),
+            datetime
+        );
+
+        let toml = encode::map([(
+            "$__toml_private_datetime",
+            encode::string("1979-05-27T07:32:00Z"),
+        )]);
+        assert_eq!(
+            super::datetime(toml.into()).unwrap().timestamp(),
+            296_638_320
+        );
+
+        let encoded = std::collections::BTreeMap::from([("at", encode::toml_datetime(datetime))]);
+        let serialized = toml::to_string(&encoded).expect("Serialize TOML");
+        assert_eq!(serialized, "at = 1979-05-27T07:32:00.5-07:00\n");
+
+        let deserialized: Value = toml::from_str(&serialized).expect("Deserialize TOML");
+        assert_eq!(
+            map(deserialized)
+                .unwrap()
+                .required("at", super::datetime)
+                .unwrap(),
+            datetime
+        );
+
+        let date = super::date(encode::string("1979-05-27")).unwrap();
+        assert_eq!(super::date(encode::date(date)).unwrap(), date);
+
+        let time = super::time(encode::string("07:32:00.999")).unwrap();
+        assert_eq!(super::time(encode::time(time)).unwrap(), time);
+
+        let timestamp = unix_timestamp(encode::i64(296_638_320)).unwrap();
+        assert_eq!(
+            unix_timestamp_millis(encode::unix_timestamp_millis(timestamp)).unwrap(),
+            timestamp
+        );
+
+        assert!(matches!(
+            super::datetime(encode::string("1979-05-27")),
+            Err(Error::InvalidValue {
+                got: Unexpected::Str(string),
+                ..
+            }) if string == "1979-05-27"
+        ));
+    }
+
+    #[test]
+    fn it_maps_deserializer_errors() {
+        use crate::encode;
+
+        assert!(matches!(
+            u8(encode::u16(300)),
+            Err(Error::OutOfRange {
+                got: Unexpected::Unsigned(300),
+                ..
+            })
+        ));
+        assert!(matches!(
+            i8(encode::i64(-200)),
+            Err(Error::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            u32(encode::string("42")),
+            Err(Error::InvalidType { expected, .. }) if expected == "u32"
+        ));
+        assert!(matches!(
+            bool(encode::u8(1)),
+            Err(Error::InvalidType { .. })
+        ));
+    }
+
+    #[test]
+    fn it_suggests_missing_fields() {
+        use crate::encode;
+
+        let value = encode::map([
+            ("hostname", encode::string("localhost")),
+            ("port", encode::u16(8080)),
+        ])
+        .into();
+
+        let mut fields = map(value).expect("Decode map");
+        assert_eq!(
+            fields.optional("user", string).expect("User is optional"),
+            None
+        );
+
+        let error = fields
+            .required("prot", u16)
+            .expect_err("Port is misspelled");
+        assert!(matches!(
+            &error,
+            Error::FieldMissing { available, suggestion, .. }
+                if available == &["hostname", "port"] && suggestion.as_deref() == Some("port")
+        ));
+        assert_eq!(
+            error.to_string(),
+            "missing field `prot` (did you mean `port`?)"
+        );
+
+        let error = fields
+            .required("user", string)
+            .expect_err("User is missing");
+        assert!(matches!(
+            error,
+            Error::FieldMissing {
+                suggestion: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn it_suggests_unknown_fields_and_variants() {
+        use crate::encode;
+
+        let value = encode::map([
+            ("name", encode::string("decoder")),
+            ("colour", encode::string("red")),
+        ])
+        .into();
+
+        let mut fields = map(value).expect("Decode map").strict();
+        let _ = fields.required("name", string).expect("Name is present");
+        let _ = fields.optional("color", string).expect("Color is optional");
+
+        let error = fields.finish().expect_err("Colour is unknown");
+        assert!(matches!(
+            &error,
+            Error::UnknownField { field, expected, .. }
+                if field == "colour" && expected == &["name", "color"]
+        ));
+        assert_eq!(error.suggestion(), Some("color"));
+        assert_eq!(
+            error.to_string(),
+            "unknown field (field: colour, expected one of: name, color) (did you mean `color`?)"
+        );
+
+        let error = Error::unknown_variant("Gren", &["Red", "Green", "Blue"]);
+        assert_eq!(error.suggestion(), Some("Green"));
+
+        let error = Error::unknown_variant("Purple", &["Red", "Green", "Blue"]);
+        assert_eq!(error.suggestion(), None);
+
+        let mut fields =
+            map(encode::map([("name", encode::string("decoder"))]).into()).expect("Decode map");
+        let _ = fields.required("name", string).expect("Name is present");
+        assert!(fields.finish().is_ok());
+
+        let fields =
+            map(encode::map([("colour", encode::string("red"))]).into()).expect("Decode map");
+        assert_eq!(
+            fields.finish().expect_err("Colour is unknown").to_string(),
+            "unknown field (field: colour)"
+        );
+    }
+
+    #[test]
+    fn it_decodes_aliases_and_case_conventions() {
+        use crate::{Case, encode};
+
+        let value: Value = encode::map([("userName", encode::string("ferris"))]).into();
+
+        let mut fields = map(value.clone()).expect("Decode map");
+        assert_eq!(
+            fields
+                .required_any(&["user_name", "userName"], string)
+                .expect("Alias is present"),
+            "ferris"
+        );
+
+        let error = fields
+            .required_any(&["user_name", "userName", "UserName"], string)
+            .expect_err("Aliases are missing");
+        assert_eq!(
+            error.to_string(),
+            "missing field `user_name` (also tried: `userName`, `UserName`)"
+        );
+        assert_eq!(fields.optional_any(&["user_name"], string).ok(), Some(None));
+
+        let mut fields = map(value).expect("Decode map").case(Case::Snake);
+        assert_eq!(
+            fields
+                .required("UserName", string)
+                .expect("Key is normalized"),
+            "ferris"
+        );
+
+        let encoded: Value = encode::map([("user_name", encode::string("ferris"))])
+            .case(Case::Pascal)
+            .into();
+        assert_eq!(
+            serde_json::to_string(&encoded).expect("Serialize JSON"),
+            r#"{"UserName":"ferris"}"#
+        );
+    }
+
+    #[test]
+    fn it_decodes_defaults_and_nullable_fields() {
+        use crate::{Field, encode};
+
+        let value: Value =
+            serde_json::from_str(r#"{ "name": null, "port": 80 }"#).expect("Deserialize JSON");
+
+        let mut fields = map(value.clone()).expect("Decode map");
+        assert_eq!(fields.with_default("port", u16, 8080).ok(), Some(80));
+        assert_eq!(
+            fields.with_default("name", string, "-".to_owned()).ok(),
+            Some("-".to_owned())
+        );
+        assert_eq!(fields.or_default("retries", u8).ok(), Some(0));
+
+        let mut fields = map(value).expect("Decode map");
+        assert_eq!(fields.nullable("name", string).ok(), Some(Field::Null));
+        assert_eq!(fields.nullable("port", u16).ok(), Some(Field::Present(80)));
+        assert_eq!(fields.nullable("host", string).ok(), Some(Field::Missing));
+        assert!(
+            fields
+                .nullable("port", u16)
+                .is_ok_and(|port| port.present().is_none())
+        );
+
+        let value: Value = encode::map([
+            ("name", encode::optional(encode::string, None::<&str>)),
+            ("port", encode::optional(encode::u16, None)),
+        ])
+        .into();
+
+        let mut fields = map(value).expect("Decode map");
+        assert_eq!(fields.optional("name", string).ok(), Some(None));
+        assert_eq!(fields.optional_any(&["port"], u16).ok(), Some(None));
+    }
+
+    #[test]
+    fn it_decodes_flattened_fields() {
+        #[derive(Debug, PartialEq)]
+        struct Database {
+            host: String,
+            port: u16,
+        }
+
+        impl Database {
+            fn decode(fields: &mut Map) -> Result<Self> {
+                Ok(Self {
+                    host: fields.required("host", string)?,
+                    port: fields.required("port", u16)?,
+                })
+            }
+        }
+
+        let server =
+            strict(|server| Ok((server.required("name", string)?, Database::decode(server)?)));
+
+        let value: Value = serde_json::from_str(r#"{ "name": "a", "host": "b", "port": 1 }"#)
+            .expect("Deserialize JSON");
+        let (name, database) = server.run(value).expect("Decode server");
+        assert_eq!(name, "a");
+        assert_eq!(
+            database,
+            Database {
+                host: "b".to_owned(),
+                port: 1
+            }
+        );
+
+        let value: Value =
+            serde_json::from_str(r#"{ "name": "a", "host": "b", "port": 1, "prot": 2 }"#)
+                .expect("Deserialize JSON");
+        let error = server.run(value).expect_err("Prot is unknown");
+        assert_eq!(error.suggestion(), Some("port"));
+
+        let value: Value = serde_json::from_str(r#"{ "host": "b", "port": 1, "extra": true }"#)
+            .expect("Deserialize JSON");
+        assert!(fields(Database::decode).run(value).is_ok());
+    }
 }
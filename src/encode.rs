@@ -4,7 +4,13 @@ use crate::{Map, Value};
 
 use decoder_value::Value as Raw;
 use serde::Serialize;
-use std::time::Duration;
+use std::ffi::OsStr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64,
+};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
 /// Encodes a [`bool`](prim@bool).
 pub fn bool(value: bool) -> Value {
@@ -61,11 +67,90 @@ pub fn f64(value: f64) -> Value {
     serialize(value)
 }
 
+/// Encodes a [`NonZeroU8`].
+pub fn non_zero_u8(value: NonZeroU8) -> Value {
+    u8(value.get())
+}
+
+/// Encodes a [`NonZeroU16`].
+pub fn non_zero_u16(value: NonZeroU16) -> Value {
+    u16(value.get())
+}
+
+/// Encodes a [`NonZeroU32`].
+pub fn non_zero_u32(value: NonZeroU32) -> Value {
+    u32(value.get())
+}
+
+/// Encodes a [`NonZeroU64`].
+pub fn non_zero_u64(value: NonZeroU64) -> Value {
+    u64(value.get())
+}
+
+/// Encodes a [`NonZeroI8`].
+pub fn non_zero_i8(value: NonZeroI8) -> Value {
+    i8(value.get())
+}
+
+/// Encodes a [`NonZeroI16`].
+pub fn non_zero_i16(value: NonZeroI16) -> Value {
+    i16(value.get())
+}
+
+/// Encodes a [`NonZeroI32`].
+pub fn non_zero_i32(value: NonZeroI32) -> Value {
+    i32(value.get())
+}
+
+/// Encodes a [`NonZeroI64`].
+pub fn non_zero_i64(value: NonZeroI64) -> Value {
+    i64(value.get())
+}
+
+/// Encodes a [`char`](prim@char).
+pub fn char(value: char) -> Value {
+    serialize(value)
+}
+
 /// Encodes a [`String`].
 pub fn string(value: impl Into<String>) -> Value {
     serialize(value.into())
 }
 
+/// Encodes a [`Path`].
+///
+/// Any non-unicode sequences are replaced with [`U+FFFD REPLACEMENT CHARACTER`](std::char::REPLACEMENT_CHARACTER).
+pub fn path(value: impl AsRef<Path>) -> Value {
+    string(value.as_ref().to_string_lossy())
+}
+
+/// Encodes an [`OsStr`].
+///
+/// Any non-unicode sequences are replaced with [`U+FFFD REPLACEMENT CHARACTER`](std::char::REPLACEMENT_CHARACTER).
+pub fn os_str(value: impl AsRef<OsStr>) -> Value {
+    string(value.as_ref().to_string_lossy())
+}
+
+/// Encodes an [`IpAddr`] as a string.
+pub fn ip_addr(value: IpAddr) -> Value {
+    string(value.to_string())
+}
+
+/// Encodes an [`Ipv4Addr`] as a string.
+pub fn ipv4_addr(value: Ipv4Addr) -> Value {
+    string(value.to_string())
+}
+
+/// Encodes an [`Ipv6Addr`] as a string.
+pub fn ipv6_addr(value: Ipv6Addr) -> Value {
+    string(value.to_string())
+}
+
+/// Encodes a [`SocketAddr`] as a string.
+pub fn socket_addr(value: SocketAddr) -> Value {
+    string(value.to_string())
+}
+
 /// Encodes a [`Duration`].
 pub fn duration(duration: Duration) -> Value {
     map([
@@ -75,6 +160,162 @@ pub fn duration(duration: Duration) -> Value {
     .into()
 }
 
+/// Encodes a [`Duration`] in its canonical human-friendly representation (e.g. `1m30s`).
+///
+/// See [`decode::duration_human`](crate::decode::duration_human).
+pub fn duration_human(duration: Duration) -> Value {
+    const UNITS: &[(&str, u128)] = &[
+        ("d", 24 * 60 * 60 * 1_000_000_000),
+        ("h", 60 * 60 * 1_000_000_000),
+        ("m", 60 * 1_000_000_000),
+        ("s", 1_000_000_000),
+        ("ms", 1_000_000),
+        ("us", 1_000),
+        ("ns", 1),
+    ];
+
+    let mut nanos = duration.as_nanos();
+
+    if nanos == 0 {
+        return string("0s");
+    }
+
+    let mut output = String::new();
+
+    for (name, unit) in UNITS {
+        if nanos >= *unit {
+            output.push_str(&(nanos / unit).to_string());
+            output.push_str(name);
+
+            nanos %= unit;
+        }
+    }
+
+    string(output)
+}
+
+/// Encodes a number of bytes in its canonical human-friendly representation (e.g. `512MiB`).
+///
+/// The largest binary unit that represents the number exactly is used, falling back
+/// to decimal units and, finally, bytes.
+///
+/// See [`decode::byte_size`](crate::decode::byte_size).
+pub fn byte_size(bytes: u64) -> Value {
+    const UNITS: &[(&str, u64)] = &[
+        ("PiB", 1 << 50),
+        ("TiB", 1 << 40),
+        ("GiB", 1 << 30),
+        ("MiB", 1 << 20),
+        ("KiB", 1 << 10),
+        ("PB", 1_000_000_000_000_000),
+        ("TB", 1_000_000_000_000),
+        ("GB", 1_000_000_000),
+        ("MB", 1_000_000),
+        ("kB", 1_000),
+    ];
+
+    let (name, unit) = UNITS
+        .iter()
+        .copied()
+        .find(|(_, unit)| bytes > 0 && bytes.is_multiple_of(*unit))
+        .unwrap_or(("B", 1));
+
+    string(format!("{}{name}", bytes / unit))
+}
+
+/// Encodes a [`SystemTime`] as the seconds and nanoseconds elapsed since the
+/// [`UNIX_EPOCH`].
+///
+/// Times earlier than the [`UNIX_EPOCH`] have negative seconds, while their
+/// nanoseconds are always positive (i.e. `-1.25s` is `-2` seconds and `750_000_000`
+/// nanoseconds).
+///
+/// [`UNIX_EPOCH`]: std::time::UNIX_EPOCH
+pub fn system_time(value: SystemTime) -> Value {
+    let (secs, nanos) = match value.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(elapsed) => (u64(elapsed.as_secs()), elapsed.subsec_nanos()),
+        Err(error) => {
+            let remaining = error.duration();
+            let secs = 0_i64.saturating_sub_unsigned(remaining.as_secs());
+
+            match remaining.subsec_nanos() {
+                0 => (i64(secs), 0),
+                nanos => (i64(secs.saturating_sub(1)), 1_000_000_000 - nanos),
+            }
+        }
+    };
+
+    map([("secs", secs), ("nanos", u32(nanos))]).into()
+}
+
+/// Encodes a date-time as an RFC 3339 string (e.g. `1979-05-27T07:32:00Z`).
+///
+/// Use [`toml_datetime`] to serialize it as a native TOML datetime.
+#[cfg(feature = "chrono")]
+pub fn datetime<Tz>(datetime: chrono::DateTime<Tz>) -> Value
+where
+    Tz: chrono::TimeZone,
+    Tz::Offset: std::fmt::Display,
+{
+    string(datetime.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+}
+
+/// Encodes a calendar date as a string (e.g. `1979-05-27`).
+#[cfg(feature = "chrono")]
+pub fn date(date: chrono::NaiveDate) -> Value {
+    string(date.format("%Y-%m-%d").to_string())
+}
+
+/// Encodes a time of day as a string (e.g. `07:32:00`).
+#[cfg(feature = "chrono")]
+pub fn time(time: chrono::NaiveTime) -> Value {
+    string(time.format("%H:%M:%S%.f").to_string())
+}
+
+/// Encodes a date-time as the number of seconds since the Unix epoch.
+#[cfg(feature = "chrono")]
+pub fn unix_timestamp<Tz: chrono::TimeZone>(datetime: chrono::DateTime<Tz>) -> Value {
+    i64(datetime.timestamp())
+}
+
+/// Encodes a date-time as the number of milliseconds since the Unix epoch.
+#[cfg(feature = "chrono")]
+pub fn unix_timestamp_millis<Tz: chrono::TimeZone>(datetime: chrono::DateTime<Tz>) -> Value {
+    i64(datetime.timestamp_millis())
+}
+
+/// Wraps a date-time so the TOML format serializes it as a native datetime
+/// (e.g. `at = 1979-05-27T07:32:00Z`) instead of a string.
+///
+/// Other formats serialize it as a map, which the date and time decoders also accept.
+#[cfg(feature = "chrono")]
+pub fn toml_datetime<Tz>(datetime: chrono::DateTime<Tz>) -> TomlDatetime
+where
+    Tz: chrono::TimeZone,
+    Tz::Offset: std::fmt::Display,
+{
+    TomlDatetime(datetime.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+}
+
+/// A date-time that serializes as a native TOML datetime, created by [`toml_datetime`].
+#[cfg(feature = "chrono")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TomlDatetime(String);
+
+#[cfg(feature = "chrono")]
+impl Serialize for TomlDatetime {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        /// The name of the struct that the TOML serializer recognizes as a datetime.
+        const NAME: &str = "$__toml_private_Datetime";
+
+        let mut s = serializer.serialize_struct(NAME, 1)?;
+        s.serialize_field(crate::decode::TOML_DATETIME, &self.0)?;
+        s.end()
+    }
+}
+
 /// Creates a [`Map`] of fields from the given iterator.
 pub fn map<'a>(fields: impl IntoIterator<Item = (&'a str, Value)>) -> Map {
     Map::from_iter(fields.into_iter().map(|(key, value)| (string(key), value)))
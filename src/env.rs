@@ -0,0 +1,266 @@
+//! Load a [`Value`] from environment variables.
+//!
+//! Variables are nested by splitting their names with a separator; all the leaves
+//! are strings. For instance, with the `APP` prefix and the `__` separator,
+//! `APP__DATABASE__PORT=5432` becomes `{ "database": { "port": "5432" } }`.
+//!
+//! Since leaves are always strings, you may want to combine [`decode::lenient`]
+//! with the primitive decoders:
+//!
+//! ```rust
+//! use decoder::decode::{lenient, map, u16};
+//! use decoder::env::Env;
+//!
+//! let env = Env::from_vars("APP", "__", [("APP__DATABASE__PORT", "5432")])
+//!     .expect("Load variables");
+//!
+//! let port = env
+//!     .run(|value| {
+//!         map(value)?.required("database", |value| {
+//!             map(value)?.required("port", lenient(u16))
+//!         })
+//!     })
+//!     .expect("Decode port");
+//!
+//! assert_eq!(port, 5432);
+//! ```
+//!
+//! [`decode::lenient`]: crate::decode::lenient
+use crate::origin::{Conflict, Origins};
+use crate::value::Raw;
+use crate::{Decoder, Error, Result, Segment, Value};
+
+use indexmap::IndexMap;
+
+/// A [`Value`] loaded from environment variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Env {
+    value: Raw,
+    variables: Origins,
+}
+
+impl Env {
+    /// Loads the environment variables of the current process with the given
+    /// prefix and separator.
+    ///
+    /// Variables whose name or value are not valid unicode are ignored.
+    ///
+    /// See [`Env::from_vars`] for the errors.
+    pub fn load(prefix: &str, separator: &str) -> Result<Self> {
+        Self::from_vars(
+            prefix,
+            separator,
+            std::env::vars_os().filter_map(|(name, value)| {
+                Some((name.into_string().ok()?, value.into_string().ok()?))
+            }),
+        )
+    }
+
+    /// Builds an [`Env`] out of the given variables with the given prefix and
+    /// separator.
+    ///
+    /// Variables that do not start with the prefix followed by the separator are
+    /// ignored. Keys are lowercased. If the prefix is empty, every variable is used.
+    ///
+    /// Fails if a variable nests under another one (e.g. `APP__DATABASE` and
+    /// `APP__DATABASE__PORT`).
+    pub fn from_vars<K, V>(
+        prefix: &str,
+        separator: &str,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self>
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{prefix}{separator}")
+        };
+
+        let mut vars: Vec<_> = vars
+            .into_iter()
+            .filter(|(name, _)| name.as_ref().starts_with(&prefix))
+            .collect();
+
+        vars.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+
+        let mut value = IndexMap::new();
+        let mut variables = Origins::default();
+
+        for (name, variable) in vars {
+            let name = name.as_ref();
+
+            let keys: Vec<_> = name[prefix.len()..]
+                .split(separator)
+                .map(str::to_lowercase)
+                .collect();
+
+            if keys.iter().any(String::is_empty) {
+                continue;
+            }
+
+            variables
+                .insert(
+                    &mut value,
+                    &keys,
+                    Raw::String(variable.into()),
+                    name,
+                    |previous, value| *previous = value,
+                )
+                .map_err(|Conflict(previous)| {
+                    Error::custom(format!(
+                        "environment variable {name} conflicts with {previous}"
+                    ))
+                })?;
+        }
+
+        Ok(Self {
+            value: Raw::Map(value),
+            variables,
+        })
+    }
+
+    /// Returns the [`Value`] of the [`Env`].
+    pub fn value(&self) -> Value {
+        Value(self.value.clone())
+    }
+
+    /// Returns the name of the variable that supplied the value at the given path, if any.
+    pub fn variable(&self, path: &[Segment]) -> Option<&str> {
+        self.variables.get(path)
+    }
+
+    /// Annotates the given [`Error`] with the name of the variable that supplied the
+    /// value that caused it.
+    pub fn annotate(&self, error: Error) -> Error {
+        self.variables.annotate(error, describe)
+    }
+
+    /// Runs a [`Decoder`] on the [`Value`] of the [`Env`], annotating any [`Error`]
+    /// with the variable that supplied the invalid value.
+    pub fn run<T>(&self, decoder: impl Decoder<Output = T>) -> Result<T> {
+        self.variables.run(self.value(), decoder, describe)
+    }
+}
+
+/// Loads a [`Value`] from the environment variables of the current process with
+/// the given prefix and separator.
+///
+/// See [`Env::load`].
+pub fn from_env(prefix: &str, separator: &str) -> Result<Value> {
+    Env::load(prefix, separator).map(|env| env.value())
+}
+
+fn describe(variable: &str) -> String {
+    format!("environment variable {variable}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{bool, lenient, map, string, u16};
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Settings {
+        debug: bool,
+        http: Http,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Http {
+        host: String,
+        port: u16,
+    }
+
+    impl Settings {
+        fn decode(value: Value) -> Result<Self> {
+            let mut settings = map(value)?;
+
+            Ok(Self {
+                debug: settings.required("debug", lenient(bool))?,
+                http: settings.required("http", Http::decode)?,
+            })
+        }
+    }
+
+    impl Http {
+        fn decode(value: Value) -> Result<Self> {
+            let mut http = map(value)?;
+
+            Ok(Self {
+                host: http.required("host", string)?,
+                port: http.required("port", lenient(u16))?,
+            })
+        }
+    }
+
+    #[test]
+    fn it_decodes_variables() {
+        let env = Env::from_vars(
+            "APP",
+            "__",
+            [
+                ("APP__DEBUG", "true"),
+                ("APP__HTTP__HOST", "1234"),
+                ("APP__HTTP__PORT", "5432"),
+                ("OTHER__HTTP__PORT", "1"),
+            ],
+        )
+        .expect("Load variables");
+
+        assert_eq!(
+            env.run(Settings::decode).expect("Decode settings"),
+            Settings {
+                debug: true,
+                http: Http {
+                    host: "1234".to_owned(),
+                    port: 5432,
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn it_mentions_variables_in_errors() {
+        let env = Env::from_vars(
+            "APP",
+            "__",
+            [("APP__DEBUG", "false"), ("APP__HTTP__PORT", "70000")],
+        )
+        .expect("Load variables");
+
+        let error = env.run(Settings::decode).expect_err("Host is missing");
+        assert!(!error.to_string().contains("environment variable"));
+
+        let env = Env::from_vars(
+            "APP",
+            "__",
+            [
+                ("APP__DEBUG", "false"),
+                ("APP__HTTP__HOST", "localhost"),
+                ("APP__HTTP__PORT", "70000"),
+            ],
+        )
+        .expect("Load variables");
+
+        let error = env.run(Settings::decode).expect_err("Port is out of range");
+        assert!(
+            error
+                .to_string()
+                .contains("environment variable APP__HTTP__PORT")
+        );
+    }
+
+    #[test]
+    fn it_rejects_nested_variables_under_a_leaf() {
+        let error = Env::from_vars("APP", "__", [("APP__HTTP__PORT", "1"), ("APP__HTTP", "x")])
+            .expect_err("Variables conflict");
+
+        assert_eq!(
+            error.to_string(),
+            "custom error: environment variable APP__HTTP__PORT conflicts with APP__HTTP"
+        );
+    }
+}
@@ -1,8 +1,8 @@
 pub use decoder_value::Unexpected;
 
-use crate::Value;
-
-use std::collections::BTreeMap;
+use decoder_value::DeserializerError;
+use std::borrow::Cow;
+use std::fmt;
 use std::sync::Arc;
 
 /// A decoder error.
@@ -12,14 +12,60 @@ pub enum Error {
     /// A type was invalid.
     #[error("invalid type (expected: {expected}, got: {got:?})")]
     InvalidType {
-        expected: &'static str,
+        expected: Cow<'static, str>,
+        got: Unexpected,
+    },
+    /// A value had the right type, but was invalid.
+    #[error("invalid value (expected: {expected}, got: {got:?})")]
+    InvalidValue {
+        expected: Cow<'static, str>,
+        got: Unexpected,
+    },
+    /// A sequence or a map had an invalid number of elements.
+    #[error("invalid length (expected: {expected}, got: {got})")]
+    InvalidLength {
+        expected: Cow<'static, str>,
+        got: usize,
+    },
+    /// A number was out of the range of its type.
+    #[error("out of range (expected: {expected}, got: {got:?})")]
+    OutOfRange {
+        expected: Cow<'static, str>,
         got: Unexpected,
     },
+    /// A variant of an enum was unknown.
+    #[error(
+        "unknown variant (variant: {variant}{}){}",
+        expected_one_of(.expected),
+        did_you_mean(.suggestion)
+    )]
+    UnknownVariant {
+        variant: String,
+        expected: Vec<String>,
+        suggestion: Option<String>,
+    },
+    /// A field of a map was unknown.
+    #[error(
+        "unknown field (field: {field}{}){}",
+        expected_one_of(.expected),
+        did_you_mean(.suggestion)
+    )]
+    UnknownField {
+        field: String,
+        expected: Vec<String>,
+        suggestion: Option<String>,
+    },
     /// A field was missing.
-    #[error("missing field (key: {key}, map: {map:?})")]
+    #[error(
+        "missing field `{key}`{}{}",
+        also_tried(.aliases),
+        did_you_mean(.suggestion)
+    )]
     FieldMissing {
         key: String,
-        map: BTreeMap<Value, Value>,
+        aliases: Vec<String>,
+        available: Vec<String>,
+        suggestion: Option<String>,
     },
     /// A custom error.
     #[error("custom error: {0}")]
@@ -27,6 +73,24 @@ pub enum Error {
     /// A deserializer error.
     #[error("deserializer error: {0}")]
     Deserializer(Arc<dyn std::error::Error + Send + Sync>),
+    /// An error inside a field of a map.
+    #[error("field `{key}`: {error}")]
+    Field { key: String, error: Box<Error> },
+    /// An error inside an item of a sequence.
+    #[error("item {index}: {error}")]
+    Item { index: usize, error: Box<Error> },
+    /// An error in a value supplied by a specific origin (e.g. a file).
+    #[error("{error} (origin: {origin})")]
+    Origin { origin: String, error: Box<Error> },
+}
+
+/// A segment of the location of an [`Error`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Segment {
+    /// The key of a field in a map.
+    Key(String),
+    /// The index of an item in a sequence.
+    Index(usize),
 }
 
 impl Error {
@@ -35,13 +99,285 @@ impl Error {
         Self::Custom(error.to_string())
     }
 
+    /// Creates an [`Error::UnknownVariant`] with the given variant and the
+    /// expected ones.
+    ///
+    /// If the variant looks like a typo of an expected one, it is suggested.
+    pub fn unknown_variant(variant: impl Into<String>, expected: &[&str]) -> Self {
+        let variant = variant.into();
+        let expected: Vec<String> = expected
+            .iter()
+            .map(|variant| (*variant).to_owned())
+            .collect();
+
+        Self::UnknownVariant {
+            suggestion: suggest(&variant, &expected).map(str::to_owned),
+            variant,
+            expected,
+        }
+    }
+
+    /// Creates an [`Error::UnknownField`] with the given field and the
+    /// expected ones.
+    ///
+    /// If the field looks like a typo of an expected one, it is suggested.
+    pub fn unknown_field(field: impl Into<String>, expected: &[&str]) -> Self {
+        Self::unknown_field_in(
+            field.into(),
+            expected.iter().map(|field| (*field).to_owned()).collect(),
+        )
+    }
+
+    /// Returns a stable code identifying the kind of the innermost [`Error`]
+    /// (e.g. `invalid_type`).
+    ///
+    /// Codes never change across versions, which makes them suitable for
+    /// matching on the client side (e.g. for translations).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidType { .. } => "invalid_type",
+            Self::InvalidValue { .. } => "invalid_value",
+            Self::InvalidLength { .. } => "invalid_length",
+            Self::OutOfRange { .. } => "out_of_range",
+            Self::UnknownVariant { .. } => "unknown_variant",
+            Self::UnknownField { .. } => "unknown_field",
+            Self::FieldMissing { .. } => "missing_field",
+            Self::Custom(_) => "custom",
+            Self::Deserializer(_) => "deserializer",
+            Self::Field { error, .. } | Self::Item { error, .. } | Self::Origin { error, .. } => {
+                error.code()
+            }
+        }
+    }
+
+    /// Returns the "did you mean" suggestion of the innermost [`Error`], if any.
+    pub fn suggestion(&self) -> Option<&str> {
+        match self.root() {
+            Self::UnknownVariant { suggestion, .. }
+            | Self::UnknownField { suggestion, .. }
+            | Self::FieldMissing { suggestion, .. } => suggestion.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the location of the [`Error`] inside the decoded [`Value`](crate::Value).
+    pub fn path(&self) -> Vec<Segment> {
+        let mut path = Vec::new();
+        let mut error = self;
+
+        loop {
+            match error {
+                Self::Field { key, error: inner } => {
+                    path.push(Segment::Key(key.clone()));
+                    error = inner;
+                }
+                Self::Item {
+                    index,
+                    error: inner,
+                } => {
+                    path.push(Segment::Index(*index));
+                    error = inner;
+                }
+                Self::Origin { error: inner, .. } => {
+                    error = inner;
+                }
+                _ => return path,
+            }
+        }
+    }
+
+    /// Returns the innermost [`Error`], without any location or origin.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Field { error, .. } | Self::Item { error, .. } | Self::Origin { error, .. } => {
+                error.root()
+            }
+            _ => self,
+        }
+    }
+
+    /// Returns the outermost origin of the [`Error`], if any.
+    pub fn origin_of(&self) -> Option<&str> {
+        match self {
+            Self::Origin { origin, .. } => Some(origin),
+            Self::Field { error, .. } | Self::Item { error, .. } => error.origin_of(),
+            _ => None,
+        }
+    }
+
+    /// Annotates the [`Error`] with the origin of the value that caused it.
+    pub fn origin(self, origin: impl Into<String>) -> Self {
+        Self::Origin {
+            origin: origin.into(),
+            error: Box::new(self),
+        }
+    }
+
+    pub(crate) fn missing_field(keys: &[&str], available: Vec<String>) -> Self {
+        let suggestion = keys
+            .iter()
+            .find_map(|key| suggest(key, &available))
+            .map(str::to_owned);
+
+        Self::FieldMissing {
+            key: keys.first().copied().unwrap_or_default().to_owned(),
+            aliases: keys.iter().skip(1).map(|&key| key.to_owned()).collect(),
+            available,
+            suggestion,
+        }
+    }
+
+    pub(crate) fn unknown_field_in(field: String, expected: Vec<String>) -> Self {
+        Self::UnknownField {
+            suggestion: suggest(&field, &expected).map(str::to_owned),
+            field,
+            expected,
+        }
+    }
+
+    pub(crate) fn field(self, key: &str) -> Self {
+        Self::Field {
+            key: key.to_owned(),
+            error: Box::new(self),
+        }
+    }
+
+    pub(crate) fn item(self, index: usize) -> Self {
+        Self::Item {
+            index,
+            error: Box::new(self),
+        }
+    }
+
     pub(crate) fn deserializer(error: impl std::error::Error + Send + Sync + 'static) -> Self {
         Self::Deserializer(Arc::new(error))
     }
 }
 
-impl From<decoder_value::DeserializerError> for Error {
-    fn from(error: decoder_value::DeserializerError) -> Self {
-        Self::Deserializer(Arc::new(error))
+impl From<DeserializerError> for Error {
+    fn from(error: DeserializerError) -> Self {
+        match error {
+            DeserializerError::Custom(message) => Self::Custom(message),
+            DeserializerError::InvalidType(got, expected) => Self::InvalidType {
+                expected: expected.into(),
+                got,
+            },
+            DeserializerError::InvalidValue(got, expected) if is_out_of_range(&got, &expected) => {
+                Self::OutOfRange {
+                    expected: expected.into(),
+                    got,
+                }
+            }
+            DeserializerError::InvalidValue(got, expected) => Self::InvalidValue {
+                expected: expected.into(),
+                got,
+            },
+            DeserializerError::InvalidLength(got, expected) => Self::InvalidLength {
+                expected: expected.into(),
+                got,
+            },
+            DeserializerError::UnknownVariant(variant, expected) => {
+                Self::unknown_variant(variant, expected)
+            }
+            DeserializerError::UnknownField(field, expected) => {
+                Self::unknown_field(field, expected)
+            }
+            DeserializerError::MissingField(field) => Self::missing_field(&[field], Vec::new()),
+            DeserializerError::DuplicateField(field) => {
+                Self::Custom(format!("duplicate field: {field}"))
+            }
+        }
+    }
+}
+
+/// Returns the candidate closest to the given name, if it is close enough to be
+/// a likely typo.
+pub(crate) fn suggest<'a>(name: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let threshold = name.chars().count().div_ceil(3).max(1);
+
+    candidates
+        .iter()
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+fn also_tried(aliases: &[String]) -> String {
+    if aliases.is_empty() {
+        return String::new();
+    }
+
+    let aliases: Vec<String> = aliases.iter().map(|alias| format!("`{alias}`")).collect();
+
+    format!(" (also tried: {})", aliases.join(", "))
+}
+
+fn expected_one_of(expected: &[String]) -> String {
+    if expected.is_empty() {
+        return String::new();
+    }
+
+    format!(", expected one of: {}", expected.join(", "))
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    suggestion
+        .as_ref()
+        .map(|suggestion| format!(" (did you mean `{suggestion}`?)"))
+        .unwrap_or_default()
+}
+
+/// Returns true if a number was rejected by the primitive [`Deserialize`] implementation
+/// of a numeric type; which only happens when it does not fit.
+///
+/// [`Deserialize`]: serde::Deserialize
+fn is_out_of_range(got: &Unexpected, expected: &str) -> bool {
+    matches!(
+        got,
+        Unexpected::Unsigned(_) | Unexpected::Signed(_) | Unexpected::Float(_)
+    ) && matches!(
+        expected,
+        "u8" | "u16"
+            | "u32"
+            | "u64"
+            | "u128"
+            | "usize"
+            | "i8"
+            | "i16"
+            | "i32"
+            | "i64"
+            | "i128"
+            | "isize"
+            | "f32"
+            | "f64"
+    )
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(key) => f.write_str(key),
+            Self::Index(index) => write!(f, "[{index}]"),
+        }
     }
 }
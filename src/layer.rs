@@ -0,0 +1,325 @@
+//! Merge layers of [`Value`]s, keeping track of where each value comes from.
+//!
+//! This is useful to load configuration from multiple sources (e.g. defaults, files,
+//! environment variables, command-line arguments) and decode the result at once:
+//!
+//! ```rust
+//! use decoder::decode::{map, sequence, string, u16};
+//! use decoder::encode;
+//! use decoder::layer::{Layers, Strategy};
+//!
+//! let defaults = encode::map([
+//!     ("port", encode::u16(80)),
+//!     ("plugins", encode::sequence(encode::string, ["auth"])),
+//! ]);
+//! let user = encode::map([
+//!     ("port", encode::string("eighty")),
+//!     ("plugins", encode::sequence(encode::string, ["cache"])),
+//! ]);
+//!
+//! let layers = Layers::new()
+//!     .strategy("plugins", Strategy::Append)
+//!     .layer("defaults", defaults.into())
+//!     .layer("user.toml", user.into());
+//!
+//! let plugins: Vec<String> = layers
+//!     .run(|value| map(value)?.required("plugins", sequence(string)))
+//!     .expect("Decode plugins");
+//!
+//! assert_eq!(plugins, ["auth", "cache"]);
+//!
+//! let error = layers
+//!     .run(|value| map(value)?.required("port", u16))
+//!     .expect_err("Port is invalid");
+//!
+//! assert!(error.to_string().contains("user.toml"));
+//! ```
+use crate::origin::Origins;
+use crate::value::Raw;
+use crate::{Decoder, Error, Result, Segment, Value};
+
+use indexmap::IndexMap;
+use std::collections::BTreeMap;
+
+/// A strategy used to merge a [`Value`] into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Merges maps field by field, replacing any other value.
+    #[default]
+    Merge,
+    /// Replaces the value as a whole.
+    Replace,
+    /// Appends the items of sequences, replacing any other value.
+    Append,
+}
+
+/// A stack of [`Value`] layers, where later layers take precedence over earlier ones.
+#[derive(Debug, Clone, Default)]
+pub struct Layers {
+    value: Option<Raw>,
+    strategies: BTreeMap<Vec<Segment>, Strategy>,
+    origins: Origins,
+}
+
+impl Layers {
+    /// Creates a new empty set of [`Layers`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the [`Strategy`] used to merge the value at the given path.
+    ///
+    /// The path is a sequence of keys separated by dots (e.g. `database.replicas`).
+    /// Values without a specific [`Strategy`] use [`Strategy::Merge`].
+    pub fn strategy(mut self, path: &str, strategy: Strategy) -> Self {
+        let path = if path.is_empty() {
+            Vec::new()
+        } else {
+            path.split('.')
+                .map(|key| Segment::Key(key.to_owned()))
+                .collect()
+        };
+
+        let _ = self.strategies.insert(path, strategy);
+        self
+    }
+
+    /// Merges a new layer with the given name on top of the current ones.
+    pub fn layer(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.push(name, value);
+        self
+    }
+
+    /// Merges a new layer with the given name on top of the current ones.
+    pub fn push(&mut self, name: impl Into<String>, value: Value) {
+        let name = name.into();
+        let mut path = Vec::new();
+
+        let mut target = self.value.take().unwrap_or(Raw::Unit);
+
+        merge(
+            &self.strategies,
+            &mut self.origins,
+            &mut target,
+            value.0,
+            &name,
+            &mut path,
+        );
+
+        self.value = Some(target);
+    }
+
+    /// Returns the name of the layer that supplied the value at the given path, if any.
+    ///
+    /// If the exact path was not supplied by any layer, the closest ancestor supplied
+    /// as a whole is used. Maps merged from several layers have no origin, so errors
+    /// about their fields as a whole (e.g. a missing field) are not annotated.
+    pub fn origin(&self, path: &[Segment]) -> Option<&str> {
+        self.origins.get(path)
+    }
+
+    /// Annotates the given [`Error`] with the name of the layer that supplied the
+    /// value that caused it.
+    pub fn annotate(&self, error: Error) -> Error {
+        self.origins.annotate(error, str::to_owned)
+    }
+
+    /// Runs a [`Decoder`] on the merged [`Value`], annotating any [`Error`] with
+    /// the layer that supplied the invalid value.
+    pub fn run<T>(&self, decoder: impl Decoder<Output = T>) -> Result<T> {
+        self.origins.run(self.value(), decoder, str::to_owned)
+    }
+
+    /// Returns the merged [`Value`] of all the layers.
+    ///
+    /// If there are no layers, an empty map is returned.
+    pub fn value(&self) -> Value {
+        Value(
+            self.value
+                .clone()
+                .unwrap_or_else(|| Raw::Map(IndexMap::new())),
+        )
+    }
+}
+
+fn merge(
+    strategies: &BTreeMap<Vec<Segment>, Strategy>,
+    origins: &mut Origins,
+    target: &mut Raw,
+    value: Raw,
+    name: &str,
+    path: &mut Vec<Segment>,
+) {
+    let strategy = strategies.get(path).copied().unwrap_or_default();
+
+    match (strategy, target, value) {
+        (Strategy::Merge, target, Raw::Map(map)) => {
+            if !matches!(target, Raw::Map(_)) {
+                origins.clear(path);
+                *target = Raw::Map(IndexMap::new());
+            }
+
+            let Raw::Map(target) = target else {
+                unreachable!()
+            };
+
+            for (key, value) in map {
+                let Raw::String(string) = &key else {
+                    let _ = target.insert(key, value);
+                    continue;
+                };
+
+                path.push(Segment::Key(string.clone()));
+
+                merge(
+                    strategies,
+                    origins,
+                    target.entry(key).or_insert(Raw::Unit),
+                    value,
+                    name,
+                    path,
+                );
+
+                let _ = path.pop();
+            }
+        }
+        (Strategy::Append, Raw::Seq(target), Raw::Seq(items)) => {
+            for item in items {
+                path.push(Segment::Index(target.len()));
+                origins.set(path, name);
+                let _ = path.pop();
+
+                target.push(item);
+            }
+        }
+        (_, target, value) => replace(origins, target, value, name, path),
+    }
+}
+
+fn replace(origins: &mut Origins, target: &mut Raw, value: Raw, name: &str, path: &[Segment]) {
+    origins.set(path, name);
+
+    *target = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{map, sequence, string, u16};
+
+    fn json(input: &str) -> Value {
+        serde_json::from_str(input).expect("Deserialize JSON")
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Config {
+        database: Database,
+        plugins: Vec<String>,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    impl Config {
+        fn decode(value: Value) -> Result<Self> {
+            let mut config = map(value)?;
+
+            Ok(Self {
+                database: config.required("database", Database::decode)?,
+                plugins: config.required("plugins", sequence(string))?,
+            })
+        }
+    }
+
+    impl Database {
+        fn decode(value: Value) -> Result<Self> {
+            let mut database = map(value)?;
+
+            Ok(Self {
+                host: database.required("host", string)?,
+                port: database.required("port", u16)?,
+            })
+        }
+    }
+
+    #[test]
+    fn it_merges_layers() {
+        let layers = Layers::new()
+            .strategy("plugins", Strategy::Append)
+            .layer(
+                "defaults",
+                json(r#"{ "database": { "host": "localhost", "port": 5432 }, "plugins": ["a"] }"#),
+            )
+            .layer(
+                "user",
+                json(r#"{ "database": { "port": 6543 }, "plugins": ["b"] }"#),
+            );
+
+        assert_eq!(
+            layers.run(Config::decode).expect("Decode config"),
+            Config {
+                database: Database {
+                    host: "localhost".to_owned(),
+                    port: 6543,
+                },
+                plugins: vec!["a".to_owned(), "b".to_owned()],
+            }
+        );
+
+        let origin = |path: &[&str]| {
+            layers.origin(
+                &path
+                    .iter()
+                    .map(|key| Segment::Key((*key).to_owned()))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(origin(&["database", "host"]), Some("defaults"));
+        assert_eq!(origin(&["database", "port"]), Some("user"));
+    }
+
+    #[test]
+    fn it_reports_the_origin_of_errors() {
+        let layers = Layers::new()
+            .layer(
+                "defaults",
+                json(r#"{ "database": { "host": "localhost", "port": 5432 }, "plugins": [] }"#),
+            )
+            .layer("environment", json(r#"{ "database": { "port": "nope" } }"#))
+            .layer("arguments", json(r#"{ "plugins": ["a", 1] }"#));
+
+        let error = layers.run(Config::decode).expect_err("Port is invalid");
+        assert!(matches!(error, Error::Origin { ref origin, .. } if origin == "environment"));
+
+        let layers = layers.layer("fix", json(r#"{ "database": { "port": 1 } }"#));
+
+        let error = layers.run(Config::decode).expect_err("Plugin is invalid");
+        assert_eq!(
+            error.path(),
+            vec![Segment::Key("plugins".to_owned()), Segment::Index(1)]
+        );
+        assert!(matches!(error, Error::Origin { ref origin, .. } if origin == "arguments"));
+    }
+
+    #[test]
+    fn it_does_not_blame_a_layer_for_merged_maps() {
+        let layers = Layers::new()
+            .layer("defaults", json(r#"{ "database": { "port": 5432 } }"#))
+            .layer("user", json(r#"{ "database": { "port": 6543 } }"#));
+
+        let error = layers.run(Config::decode).expect_err("Host is missing");
+        assert!(matches!(error.root(), Error::FieldMissing { key, .. } if key == "host"));
+        assert_eq!(error.origin_of(), None);
+
+        let layers = layers
+            .strategy("database", Strategy::Replace)
+            .layer("override", json(r#"{ "database": { "port": 1 } }"#));
+
+        let error = layers.run(Config::decode).expect_err("Host is missing");
+        assert_eq!(error.origin_of(), Some("override"));
+    }
+}
@@ -74,14 +74,41 @@
 //! [`serde`]: https://serde.rs
 //! [`Serialize`]: https://docs.rs/serde/latest/serde/trait.Serialize.html
 //! [`Deserialize`]: https://docs.rs/serde/latest/serde/trait.Deserialize.html
+pub mod args;
+pub mod csv;
 pub mod decode;
 pub mod encode;
+pub mod env;
+pub mod layer;
+pub mod lines;
+pub mod patch;
+pub mod query;
+pub mod report;
+pub mod schema;
+pub mod validate;
+pub mod versioned;
+
+#[cfg(feature = "async")]
+pub mod stream;
+
+#[cfg(feature = "testing")]
+pub mod testing;
 
 mod error;
+mod origin;
 mod value;
 
-pub use error::Error;
-pub use value::{Map, Value};
+pub use error::{Error, Segment, Unexpected};
+pub use value::{Case, Field, Map, Value};
+
+#[cfg(feature = "async")]
+pub use stream::decode_async;
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::RangeBounds;
+use validate::{Describe, Length};
 
 /// A decoding result.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -93,6 +120,84 @@ pub trait Decoder {
 
     /// Runs the [`Decoder`].
     fn run(&self, value: Value) -> Result<Self::Output>;
+
+    /// Ensures the output of the [`Decoder`] is within the given range.
+    fn range(self, range: impl RangeBounds<Self::Output>) -> impl Decoder<Output = Self::Output>
+    where
+        Self: Sized,
+        Self::Output: PartialOrd + Debug + Describe,
+    {
+        let expected = validate::describe_range(&range);
+
+        validate::ensure(self, expected.into(), move |output| range.contains(output))
+    }
+
+    /// Ensures the output of the [`Decoder`] has at least the given length.
+    fn min_len(self, min: usize) -> impl Decoder<Output = Self::Output>
+    where
+        Self: Sized,
+        Self::Output: Length,
+    {
+        validate::ensure_length(self, format!("at least {min}").into(), move |length| {
+            length >= min
+        })
+    }
+
+    /// Ensures the output of the [`Decoder`] has at most the given length.
+    fn max_len(self, max: usize) -> impl Decoder<Output = Self::Output>
+    where
+        Self: Sized,
+        Self::Output: Length,
+    {
+        validate::ensure_length(self, format!("at most {max}").into(), move |length| {
+            length <= max
+        })
+    }
+
+    /// Ensures the output of the [`Decoder`] is not empty.
+    fn non_empty(self) -> impl Decoder<Output = Self::Output>
+    where
+        Self: Sized,
+        Self::Output: Length,
+    {
+        validate::ensure_length(self, "at least 1".into(), |length| length > 0)
+    }
+
+    /// Ensures the items of the output of the [`Decoder`] are unique.
+    ///
+    /// The [`Error`] points to the first item that repeats an earlier one.
+    fn unique<T>(self) -> impl Decoder<Output = Self::Output>
+    where
+        Self: Sized,
+        Self::Output: AsRef<[T]>,
+        T: Hash + Eq + Describe,
+    {
+        validate::unique(self)
+    }
+
+    /// Ensures the output of the [`Decoder`] matches the given regular expression.
+    #[cfg(feature = "regex")]
+    fn matches(self, regex: regex::Regex) -> impl Decoder<Output = Self::Output>
+    where
+        Self: Sized,
+        Self::Output: AsRef<str>,
+    {
+        validate::matches(self, regex)
+    }
+
+    /// Ensures the output of the [`Decoder`] satisfies the given predicate,
+    /// described by `expected` in errors.
+    fn ensure(
+        self,
+        expected: impl Into<Cow<'static, str>>,
+        predicate: impl Fn(&Self::Output) -> bool,
+    ) -> impl Decoder<Output = Self::Output>
+    where
+        Self: Sized,
+        Self::Output: Describe,
+    {
+        validate::ensure(self, expected.into(), predicate)
+    }
 }
 
 impl<F, T> Decoder for F
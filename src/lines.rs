@@ -0,0 +1,382 @@
+//! Decode and encode streams of newline-delimited values, like [JSON Lines].
+//!
+//! Like [`run`](crate::run), the functions in this module take a function to
+//! deserialize or serialize each [`Value`], so any format can be used. Input is
+//! read lazily line by line; it is never buffered as a whole.
+//!
+//! ```rust
+//! use decoder::decode::{map, string};
+//! use decoder::lines;
+//!
+//! let input = "{ \"level\": \"info\" }\nnot json\n{ \"level\": \"warn\" }\n";
+//!
+//! let levels: Vec<_> = lines::read(input.as_bytes(), |line: &str| serde_json::from_str(line))
+//!     .decode(|value| map(value)?.required("level", string))
+//!     .filter_map(|level| level.inspect_err(|error| eprintln!("{error}")).ok())
+//!     .collect();
+//!
+//! assert_eq!(levels, ["info", "warn"]);
+//! ```
+//!
+//! [JSON Lines]: https://jsonlines.org
+use crate::{Decoder, Error, Result, Value};
+
+use std::io::{self, BufRead, Read, Write};
+
+/// The default maximum length of a line read by a [`Reader`]: 16 MiB.
+pub const MAX_LINE_LENGTH: usize = 16 * 1024 * 1024;
+
+/// A reader of newline-delimited values.
+///
+/// Errors are annotated with the line that caused them. A line that is not valid
+/// UTF-8, is longer than the [maximum length](Self::max_length), or fails to
+/// deserialize does not stop the reader, so bad lines can be skipped; an I/O
+/// error does.
+#[derive(Debug)]
+pub struct Reader<R, F> {
+    input: R,
+    deserialize: F,
+    buffer: Vec<u8>,
+    max_length: usize,
+    line: usize,
+    is_done: bool,
+}
+
+/// An iterator that decodes each value of a [`Reader`] with a [`Decoder`].
+#[derive(Debug)]
+pub struct Decode<R, F, D> {
+    reader: Reader<R, F>,
+    decoder: D,
+}
+
+/// A writer of newline-delimited values.
+#[derive(Debug)]
+pub struct Writer<W, F> {
+    output: W,
+    serialize: F,
+}
+
+/// Creates a [`Reader`] of newline-delimited values, using the given function
+/// to deserialize each line into a [`Value`].
+pub fn read<R, F, E>(input: R, deserialize: F) -> Reader<R, F>
+where
+    R: BufRead,
+    F: Fn(&str) -> std::result::Result<Value, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Reader {
+        input,
+        deserialize,
+        buffer: Vec::new(),
+        max_length: MAX_LINE_LENGTH,
+        line: 0,
+        is_done: false,
+    }
+}
+
+impl<R, F, E> Reader<R, F>
+where
+    R: BufRead,
+    F: Fn(&str) -> std::result::Result<Value, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Sets the maximum length of a line, in bytes, excluding the newline.
+    ///
+    /// Longer lines are skipped without being buffered, failing with an
+    /// [`Error::InvalidLength`]. By default, it is [`MAX_LINE_LENGTH`].
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Returns the number of the last line read.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Decodes every remaining value with the given [`Decoder`].
+    ///
+    /// Errors are annotated with the line that caused them.
+    pub fn decode<D: Decoder>(self, decoder: D) -> Decode<R, F, D> {
+        Decode {
+            reader: self,
+            decoder,
+        }
+    }
+}
+
+impl<R, F, E> Iterator for Reader<R, F>
+where
+    R: BufRead,
+    F: Fn(&str) -> std::result::Result<Value, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.is_done {
+            self.buffer.clear();
+            self.line += 1;
+
+            let limit = self.max_length.saturating_add(1) as u64;
+
+            let line = match (&mut self.input)
+                .take(limit)
+                .read_until(b'\n', &mut self.buffer)
+            {
+                Ok(0) => {
+                    self.is_done = true;
+                    continue;
+                }
+                Ok(_) if self.buffer.len() > self.max_length && !self.buffer.ends_with(b"\n") => {
+                    match skip_line(&mut self.input) {
+                        Ok(skipped) => Err(Error::InvalidLength {
+                            expected: format!("a line of at most {} bytes", self.max_length).into(),
+                            got: self.buffer.len() + skipped,
+                        }),
+                        Err(error) => {
+                            self.is_done = true;
+                            Err(Error::deserializer(error))
+                        }
+                    }
+                }
+                Ok(_) => std::str::from_utf8(&self.buffer).map_err(Error::deserializer),
+                Err(error) => {
+                    self.is_done = true;
+                    Err(Error::deserializer(error))
+                }
+            };
+
+            match line {
+                Ok(line) if line.trim().is_empty() => {}
+                Ok(line) => {
+                    return Some(
+                        (self.deserialize)(line)
+                            .map_err(|error| origin(Error::deserializer(error), self.line)),
+                    );
+                }
+                Err(error) => return Some(Err(origin(error, self.line))),
+            }
+        }
+
+        None
+    }
+}
+
+impl<R, F, E, D> Iterator for Decode<R, F, D>
+where
+    R: BufRead,
+    F: Fn(&str) -> std::result::Result<Value, E>,
+    E: std::error::Error + Send + Sync + 'static,
+    D: Decoder,
+{
+    type Item = Result<D::Output>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.reader.next()?;
+
+        Some(value.and_then(|value| {
+            self.decoder
+                .run(value)
+                .map_err(|error| origin(error, self.reader.line))
+        }))
+    }
+}
+
+impl<W, F, E> Writer<W, F>
+where
+    W: Write,
+    F: Fn(&Value) -> std::result::Result<String, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Creates a new [`Writer`], using the given function to serialize each [`Value`].
+    ///
+    /// The serialized output of a [`Value`] must not contain any newlines.
+    pub fn new(output: W, serialize: F) -> Self {
+        Self { output, serialize }
+    }
+
+    /// Writes a [`Value`] followed by a newline.
+    pub fn write(&mut self, value: &Value) -> Result<()> {
+        let mut line = (self.serialize)(value).map_err(Error::deserializer)?;
+
+        if line.contains('\n') {
+            return Err(Error::custom("serialized value contains a newline"));
+        }
+
+        line.push('\n');
+
+        self.output
+            .write_all(line.as_bytes())
+            .map_err(Error::deserializer)
+    }
+
+    /// Flushes the underlying output of the [`Writer`].
+    pub fn flush(&mut self) -> Result<()> {
+        self.output.flush().map_err(Error::deserializer)
+    }
+
+    /// Returns the underlying output of the [`Writer`].
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+fn origin(error: Error, line: usize) -> Error {
+    error.origin(format!("line {line}"))
+}
+
+/// Consumes the rest of a line without buffering it, returning its length.
+fn skip_line(input: &mut impl BufRead) -> io::Result<usize> {
+    let mut skipped = 0;
+
+    loop {
+        let available = input.fill_buf()?;
+
+        match available.iter().position(|byte| *byte == b'\n') {
+            Some(end) => {
+                input.consume(end + 1);
+                return Ok(skipped + end);
+            }
+            None if available.is_empty() => return Ok(skipped),
+            None => {
+                let length = available.len();
+                input.consume(length);
+                skipped += length;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{map, string, u32};
+    use crate::encode;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Event {
+        id: u32,
+        name: String,
+    }
+
+    impl Event {
+        fn decode(value: Value) -> Result<Self> {
+            let mut event = map(value)?;
+
+            Ok(Self {
+                id: event.required("id", u32)?,
+                name: event.required("name", string)?,
+            })
+        }
+
+        fn encode(&self) -> Value {
+            encode::map([
+                ("id", encode::u32(self.id)),
+                ("name", encode::string(&self.name)),
+            ])
+            .into()
+        }
+    }
+
+    fn json(line: &str) -> serde_json::Result<Value> {
+        serde_json::from_str(line)
+    }
+
+    #[test]
+    fn it_decodes_lines_and_skips_errors() {
+        let input = r#"{ "id": 1, "name": "a" }
+
+{ "id": "2", "name": "b" }
+{ "id": 3,
+{ "id": 4, "name": "d" }"#;
+
+        let events: Vec<_> = read(input.as_bytes(), json).decode(Event::decode).collect();
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events[0].as_ref().unwrap(),
+            &Event {
+                id: 1,
+                name: "a".to_owned()
+            }
+        );
+
+        let error = events[1].as_ref().expect_err("Id is invalid");
+        assert!(matches!(error, Error::Origin { origin, .. } if origin == "line 3"));
+        assert_eq!(error.path(), [crate::Segment::Key("id".to_owned())]);
+
+        let error = events[2].as_ref().expect_err("Line is invalid JSON");
+        assert!(matches!(error, Error::Origin { origin, .. } if origin == "line 4"));
+
+        assert_eq!(events[3].as_ref().unwrap().id, 4);
+    }
+
+    #[test]
+    fn it_skips_lines_that_are_not_utf8() {
+        let input = b"{ \"id\": 1, \"name\": \"a\" }\n\xff\xfe\n{ \"id\": 3, \"name\": \"c\" }\n";
+
+        let events: Vec<_> = read(&input[..], json).decode(Event::decode).collect();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].as_ref().unwrap().id, 1);
+        assert!(matches!(&events[1], Err(Error::Origin { origin, .. }) if origin == "line 2"));
+        assert_eq!(events[2].as_ref().unwrap().id, 3);
+    }
+
+    #[test]
+    fn it_skips_lines_that_are_too_long() {
+        let input = format!(
+            "{{ \"id\": 1, \"name\": \"a\" }}\n{{ \"id\": 2, \"name\": \"{}\" }}\n{{ \"id\": 3, \"name\": \"c\" }}",
+            "b".repeat(100)
+        );
+
+        let events: Vec<_> = read(input.as_bytes(), json)
+            .max_length(30)
+            .decode(Event::decode)
+            .collect();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].as_ref().unwrap().id, 1);
+        assert!(matches!(
+            &events[1],
+            Err(Error::Origin { origin, error })
+                if origin == "line 2" && matches!(**error, Error::InvalidLength { got: 123, .. })
+        ));
+        assert_eq!(events[2].as_ref().unwrap().id, 3);
+    }
+
+    #[test]
+    fn it_encodes_lines() {
+        let events = [
+            Event {
+                id: 1,
+                name: "a".to_owned(),
+            },
+            Event {
+                id: 2,
+                name: "b\nc".to_owned(),
+            },
+        ];
+
+        let mut writer = Writer::new(Vec::new(), serde_json::to_string);
+
+        for event in &events {
+            writer.write(&event.encode()).expect("Write event");
+        }
+
+        let output = writer.into_inner();
+
+        assert_eq!(
+            String::from_utf8_lossy(&output),
+            "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\\nc\"}\n"
+        );
+
+        let decoded = read(output.as_slice(), json)
+            .decode(Event::decode)
+            .collect::<Result<Vec<_>>>()
+            .expect("Decode events");
+
+        assert_eq!(decoded, events);
+    }
+}
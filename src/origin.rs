@@ -0,0 +1,125 @@
+//! Keep track of the source that supplied each part of a [`Value`] tree.
+use crate::value::Raw;
+use crate::{Decoder, Error, Result, Segment, Value};
+
+use indexmap::IndexMap;
+use std::collections::BTreeMap;
+
+/// The sources that supplied the values of a tree, by path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Origins(BTreeMap<Vec<Segment>, String>);
+
+/// A value that does not fit the shape given by a previous one, holding the
+/// source of the previous one.
+pub(crate) struct Conflict(pub(crate) String);
+
+impl Origins {
+    /// Returns the source of the value at the given path, falling back to its
+    /// closest ancestor supplied as a whole.
+    pub(crate) fn get(&self, path: &[Segment]) -> Option<&str> {
+        (0..=path.len())
+            .rev()
+            .find_map(|len| self.0.get(&path[..len]))
+            .map(String::as_str)
+    }
+
+    /// Records the source of the value at the given path, replacing the sources
+    /// of anything below it.
+    pub(crate) fn set(&mut self, path: &[Segment], origin: &str) {
+        self.clear(path);
+        let _ = self.0.insert(path.to_vec(), origin.to_owned());
+    }
+
+    /// Forgets the sources of the value at the given path and anything below it.
+    pub(crate) fn clear(&mut self, path: &[Segment]) {
+        self.0.retain(|other, _| !other.starts_with(path));
+    }
+
+    /// Inserts a leaf at the given keys of a map, creating the maps in between,
+    /// and records its source.
+    ///
+    /// A leaf given twice is combined with the previous one by `repeat`. Fails if
+    /// the leaf would replace a map, or a map would replace a leaf.
+    pub(crate) fn insert(
+        &mut self,
+        map: &mut IndexMap<Raw, Raw>,
+        keys: &[String],
+        value: Raw,
+        origin: &str,
+        repeat: impl FnOnce(&mut Raw, Raw),
+    ) -> std::result::Result<(), Conflict> {
+        let path: Vec<_> = keys.iter().cloned().map(Segment::Key).collect();
+
+        if !insert(map, keys, value, repeat) {
+            let previous = self.get(&path).or_else(|| {
+                self.0
+                    .range(path.clone()..)
+                    .next()
+                    .filter(|(other, _)| other.starts_with(&path))
+                    .map(|(_, origin)| origin.as_str())
+            });
+
+            return Err(Conflict(previous.unwrap_or("a previous one").to_owned()));
+        }
+
+        self.set(&path, origin);
+        Ok(())
+    }
+
+    /// Annotates the given [`Error`] with the source of the value that caused it,
+    /// described by `describe`.
+    pub(crate) fn annotate(&self, error: Error, describe: impl FnOnce(&str) -> String) -> Error {
+        match self.get(&error.path()) {
+            Some(origin) => {
+                let origin = describe(origin);
+                error.origin(origin)
+            }
+            None => error,
+        }
+    }
+
+    /// Runs a [`Decoder`] on the given [`Value`], annotating any [`Error`] with the
+    /// source of the invalid value.
+    pub(crate) fn run<T>(
+        &self,
+        value: Value,
+        decoder: impl Decoder<Output = T>,
+        describe: impl FnOnce(&str) -> String,
+    ) -> Result<T> {
+        decoder
+            .run(value)
+            .map_err(|error| self.annotate(error, describe))
+    }
+}
+
+fn insert(
+    map: &mut IndexMap<Raw, Raw>,
+    keys: &[String],
+    value: Raw,
+    repeat: impl FnOnce(&mut Raw, Raw),
+) -> bool {
+    let Some((key, rest)) = keys.split_first() else {
+        return true;
+    };
+
+    let key = Raw::String(key.clone());
+
+    match (map.get_mut(&key), rest.is_empty()) {
+        (None, true) => {
+            let _ = map.insert(key, value);
+            true
+        }
+        (None, false) => {
+            let mut nested = IndexMap::new();
+            let is_inserted = insert(&mut nested, rest, value, repeat);
+            let _ = map.insert(key, Raw::Map(nested));
+            is_inserted
+        }
+        (Some(Raw::Map(nested)), false) => insert(nested, rest, value, repeat),
+        (Some(Raw::Map(_)), true) | (Some(_), false) => false,
+        (Some(previous), true) => {
+            repeat(previous, value);
+            true
+        }
+    }
+}
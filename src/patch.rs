@@ -0,0 +1,608 @@
+//! Apply and generate JSON Patches ([RFC 6902]) and JSON Merge Patches ([RFC 7396]).
+//!
+//! [RFC 6902]: https://datatracker.ietf.org/doc/html/rfc6902
+//! [RFC 7396]: https://datatracker.ietf.org/doc/html/rfc7396
+use crate::value::{Raw, equal, inner, inner_mut, is_null};
+use crate::{Decoder, Error, Result, Value, decode, encode};
+
+use indexmap::IndexMap;
+use std::fmt;
+
+/// A JSON Patch; a sequence of [`Operation`]s applied in order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Patch {
+    operations: Vec<Operation>,
+}
+
+/// An operation of a [`Patch`].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// Adds a value to an object or inserts it into an array.
+    Add { path: Pointer, value: Value },
+    /// Removes the value at the target location.
+    Remove { path: Pointer },
+    /// Replaces the value at the target location.
+    Replace { path: Pointer, value: Value },
+    /// Removes the value at `from` and adds it to `path`.
+    Move { from: Pointer, path: Pointer },
+    /// Copies the value at `from` to `path`.
+    Copy { from: Pointer, path: Pointer },
+    /// Tests that the value at the target location is equal to the given one.
+    Test { path: Pointer, value: Value },
+}
+
+/// A JSON Pointer ([RFC 6901]) referencing a location inside of a [`Value`].
+///
+/// [RFC 6901]: https://datatracker.ietf.org/doc/html/rfc6901
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Pointer {
+    tokens: Vec<String>,
+}
+
+impl Patch {
+    /// Creates a new empty [`Patch`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the [`Operation`]s of the [`Patch`].
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Returns true if the [`Patch`] has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Applies the [`Patch`] to the given [`Value`].
+    ///
+    /// The [`Patch`] is applied atomically; if any [`Operation`] fails, the
+    /// [`Value`] is left untouched.
+    pub fn apply(&self, value: &mut Value) -> Result<()> {
+        let mut target = value.0.clone();
+
+        for operation in &self.operations {
+            operation.apply(&mut target)?;
+        }
+
+        value.0 = target;
+
+        Ok(())
+    }
+
+    /// Decodes a [`Patch`].
+    pub fn decode(value: Value) -> Result<Self> {
+        decode::sequence(Operation::decode)
+            .run(value)
+            .map(|operations| Self { operations })
+    }
+
+    /// Encodes the [`Patch`].
+    pub fn encode(&self) -> Value {
+        encode::sequence(Operation::encode, &self.operations)
+    }
+}
+
+impl FromIterator<Operation> for Patch {
+    fn from_iter<T: IntoIterator<Item = Operation>>(iter: T) -> Self {
+        Self {
+            operations: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Operation {
+    /// Decodes an [`Operation`].
+    pub fn decode(value: Value) -> Result<Self> {
+        use decode::{map, string};
+
+        let mut operation = map(value)?;
+
+        let op = operation.required("op", string)?;
+        let path = operation.required("path", Pointer::decode)?;
+
+        Ok(match op.as_str() {
+            "add" => Operation::Add {
+                path,
+                value: operation.required("value", Ok)?,
+            },
+            "remove" => Operation::Remove { path },
+            "replace" => Operation::Replace {
+                path,
+                value: operation.required("value", Ok)?,
+            },
+            "move" => Operation::Move {
+                from: operation.required("from", Pointer::decode)?,
+                path,
+            },
+            "copy" => Operation::Copy {
+                from: operation.required("from", Pointer::decode)?,
+                path,
+            },
+            "test" => Operation::Test {
+                path,
+                value: operation.required("value", Ok)?,
+            },
+            _ => {
+                return Err(Error::unknown_variant(
+                    op,
+                    &["add", "remove", "replace", "move", "copy", "test"],
+                )
+                .field("op"));
+            }
+        })
+    }
+
+    /// Encodes the [`Operation`].
+    pub fn encode(&self) -> Value {
+        use encode::{map, string};
+
+        match self {
+            Operation::Add { path, value } => map([
+                ("op", string("add")),
+                ("path", path.encode()),
+                ("value", value.clone()),
+            ]),
+            Operation::Remove { path } => map([("op", string("remove")), ("path", path.encode())]),
+            Operation::Replace { path, value } => map([
+                ("op", string("replace")),
+                ("path", path.encode()),
+                ("value", value.clone()),
+            ]),
+            Operation::Move { from, path } => map([
+                ("op", string("move")),
+                ("from", from.encode()),
+                ("path", path.encode()),
+            ]),
+            Operation::Copy { from, path } => map([
+                ("op", string("copy")),
+                ("from", from.encode()),
+                ("path", path.encode()),
+            ]),
+            Operation::Test { path, value } => map([
+                ("op", string("test")),
+                ("path", path.encode()),
+                ("value", value.clone()),
+            ]),
+        }
+        .into()
+    }
+
+    fn apply(&self, target: &mut Raw) -> Result<()> {
+        match self {
+            Operation::Add { path, value } => add(target, path, value.0.clone()),
+            Operation::Remove { path } => remove(target, path).map(|_| ()),
+            Operation::Replace { path, value } => {
+                *get_mut(target, path)? = value.0.clone();
+                Ok(())
+            }
+            Operation::Move { from, path } => {
+                if path.tokens.starts_with(&from.tokens) && path != from {
+                    return Err(Error::custom(format!(
+                        "cannot move {from} into one of its children: {path}"
+                    )));
+                }
+
+                let value = remove(target, from)?;
+                add(target, path, value)
+            }
+            Operation::Copy { from, path } => {
+                let value = get_mut(target, from)?.clone();
+                add(target, path, value)
+            }
+            Operation::Test { path, value } => {
+                if equal(get_mut(target, path)?, &value.0) {
+                    Ok(())
+                } else {
+                    Err(Error::custom(format!("test failed: {path}")))
+                }
+            }
+        }
+    }
+}
+
+impl Pointer {
+    /// Returns the [`Pointer`] to the whole document.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Parses a [`Pointer`] from its string representation (e.g. `/a/b~1c/0`).
+    pub fn parse(pointer: &str) -> Result<Self> {
+        if pointer.is_empty() {
+            return Ok(Self::root());
+        }
+
+        let Some(tokens) = pointer.strip_prefix('/') else {
+            return Err(Error::custom(format!(
+                "invalid pointer (must start with /): {pointer}"
+            )));
+        };
+
+        tokens
+            .split('/')
+            .map(|token| {
+                let mut unescaped = String::with_capacity(token.len());
+                let mut chars = token.chars();
+
+                while let Some(c) = chars.next() {
+                    if c != '~' {
+                        unescaped.push(c);
+                        continue;
+                    }
+
+                    match chars.next() {
+                        Some('0') => unescaped.push('~'),
+                        Some('1') => unescaped.push('/'),
+                        _ => {
+                            return Err(Error::custom(format!(
+                                "invalid escape sequence in pointer: {pointer}"
+                            )));
+                        }
+                    }
+                }
+
+                Ok(unescaped)
+            })
+            .collect::<Result<_>>()
+            .map(|tokens| Self { tokens })
+    }
+
+    /// Appends a reference token to the [`Pointer`].
+    pub fn push(mut self, token: impl Into<String>) -> Self {
+        self.tokens.push(token.into());
+        self
+    }
+
+    /// Returns the reference tokens of the [`Pointer`].
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// Decodes a [`Pointer`].
+    pub fn decode(value: Value) -> Result<Self> {
+        Self::parse(decode::str(&value)?)
+    }
+
+    /// Encodes the [`Pointer`].
+    pub fn encode(&self) -> Value {
+        encode::string(self.to_string())
+    }
+
+    fn split_last(&self) -> Option<(&[String], &str)> {
+        self.tokens
+            .split_last()
+            .map(|(last, parent)| (parent, last.as_str()))
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Applies a JSON Merge Patch to the given [`Value`].
+pub fn merge(target: &mut Value, patch: &Value) {
+    merge_raw(&mut target.0, &patch.0);
+}
+
+/// Generates a JSON Merge Patch that turns `from` into `to`, once merged.
+///
+/// Merge patches cannot express explicit nulls nor changes inside of sequences; these
+/// are replaced as a whole.
+pub fn merge_diff(from: &Value, to: &Value) -> Value {
+    Value(merge_diff_raw(&from.0, &to.0))
+}
+
+/// Generates a [`Patch`] that turns `from` into `to`, once applied.
+pub fn diff(from: &Value, to: &Value) -> Patch {
+    let mut operations = Vec::new();
+    diff_raw(&from.0, &to.0, Pointer::root(), &mut operations);
+
+    Patch { operations }
+}
+
+fn index(token: &str, len: usize) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+
+    token.parse().ok().filter(|index| *index < len)
+}
+
+fn not_found(path: &[String]) -> Error {
+    Error::custom(format!(
+        "path not found: {}",
+        Pointer {
+            tokens: path.to_vec()
+        }
+    ))
+}
+
+fn navigate<'a>(target: &'a mut Raw, path: &[String]) -> Result<&'a mut Raw> {
+    let mut current = inner_mut(target);
+
+    for (i, token) in path.iter().enumerate() {
+        let next = match current {
+            Raw::Map(map) => map.get_mut(&Raw::String(token.clone())),
+            Raw::Seq(sequence) => {
+                let len = sequence.len();
+                index(token, len).map(|index| &mut sequence[index])
+            }
+            _ => None,
+        };
+
+        current = inner_mut(next.ok_or_else(|| not_found(&path[..=i]))?);
+    }
+
+    Ok(current)
+}
+
+fn get_mut<'a>(target: &'a mut Raw, pointer: &Pointer) -> Result<&'a mut Raw> {
+    navigate(target, &pointer.tokens)
+}
+
+fn add(target: &mut Raw, pointer: &Pointer, value: Raw) -> Result<()> {
+    let Some((parent, last)) = pointer.split_last() else {
+        *target = value;
+        return Ok(());
+    };
+
+    match navigate(target, parent)? {
+        Raw::Map(map) => {
+            let _ = map.insert(Raw::String(last.to_owned()), value);
+            Ok(())
+        }
+        Raw::Seq(sequence) if last == "-" => {
+            sequence.push(value);
+            Ok(())
+        }
+        Raw::Seq(sequence) => {
+            let index =
+                index(last, sequence.len() + 1).ok_or_else(|| not_found(&pointer.tokens))?;
+
+            sequence.insert(index, value);
+            Ok(())
+        }
+        _ => Err(not_found(&pointer.tokens)),
+    }
+}
+
+fn remove(target: &mut Raw, pointer: &Pointer) -> Result<Raw> {
+    let Some((parent, last)) = pointer.split_last() else {
+        return Err(Error::custom("cannot remove the root of the document"));
+    };
+
+    match navigate(target, parent)? {
+        Raw::Map(map) => map.shift_remove(&Raw::String(last.to_owned())),
+        Raw::Seq(sequence) => index(last, sequence.len()).map(|index| sequence.remove(index)),
+        _ => None,
+    }
+    .ok_or_else(|| not_found(&pointer.tokens))
+}
+
+fn merge_raw(target: &mut Raw, patch: &Raw) {
+    let Raw::Map(patch) = inner(patch) else {
+        *target = patch.clone();
+        return;
+    };
+
+    let target = inner_mut(target);
+
+    if !matches!(target, Raw::Map(_)) {
+        *target = Raw::Map(IndexMap::new());
+    }
+
+    let Raw::Map(map) = target else {
+        unreachable!()
+    };
+
+    for (key, value) in patch {
+        if is_null(value) {
+            let _ = map.shift_remove(key);
+        } else {
+            merge_raw(map.entry(key.clone()).or_insert(Raw::Unit), value);
+        }
+    }
+}
+
+fn merge_diff_raw(from: &Raw, to: &Raw) -> Raw {
+    let (Raw::Map(from), Raw::Map(to)) = (inner(from), inner(to)) else {
+        return to.clone();
+    };
+
+    let removed = from
+        .keys()
+        .filter(|key| !to.contains_key(*key))
+        .map(|key| (key.clone(), Raw::Unit));
+
+    let changed = to.iter().filter_map(|(key, to)| match from.get(key) {
+        Some(from) if equal(from, to) => None,
+        Some(from) => Some((key.clone(), merge_diff_raw(from, to))),
+        None => Some((key.clone(), to.clone())),
+    });
+
+    Raw::Map(removed.chain(changed).collect())
+}
+
+fn diff_raw(from: &Raw, to: &Raw, path: Pointer, operations: &mut Vec<Operation>) {
+    if equal(from, to) {
+        return;
+    }
+
+    match (inner(from), inner(to)) {
+        // Only string keys can be addressed by a pointer, so any other map is
+        // replaced as a whole
+        (Raw::Map(from), Raw::Map(to))
+            if from
+                .keys()
+                .chain(to.keys())
+                .all(|key| matches!(key, Raw::String(_))) =>
+        {
+            for key in from.keys().filter(|key| !to.contains_key(*key)) {
+                if let Raw::String(key) = key {
+                    operations.push(Operation::Remove {
+                        path: path.clone().push(key),
+                    });
+                }
+            }
+
+            for (key, to) in to {
+                let Raw::String(token) = key else {
+                    continue;
+                };
+
+                match from.get(key) {
+                    Some(from) => diff_raw(from, to, path.clone().push(token), operations),
+                    None => operations.push(Operation::Add {
+                        path: path.clone().push(token),
+                        value: Value(to.clone()),
+                    }),
+                }
+            }
+        }
+        (Raw::Seq(from), Raw::Seq(to)) => {
+            for (i, (from, to)) in from.iter().zip(to).enumerate() {
+                diff_raw(from, to, path.clone().push(i.to_string()), operations);
+            }
+
+            for i in (to.len()..from.len()).rev() {
+                operations.push(Operation::Remove {
+                    path: path.clone().push(i.to_string()),
+                });
+            }
+
+            for to in to.iter().skip(from.len()) {
+                operations.push(Operation::Add {
+                    path: path.clone().push("-"),
+                    value: Value(to.clone()),
+                });
+            }
+        }
+        _ => operations.push(Operation::Replace {
+            path,
+            value: Value(to.clone()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(input: &str) -> Value {
+        serde_json::from_str(input).expect("Deserialize JSON")
+    }
+
+    #[test]
+    fn it_applies_patches() {
+        let mut document = json(r#"{ "name": "decoder", "tags": ["rust"], "draft": true }"#);
+
+        let patch = Patch::decode(json(
+            r#"[
+                { "op": "test", "path": "/name", "value": "decoder" },
+                { "op": "add", "path": "/tags/-", "value": "serde" },
+                { "op": "remove", "path": "/draft" },
+                { "op": "copy", "from": "/tags/0", "path": "/language" },
+                { "op": "replace", "path": "/name", "value": "decoder_value" }
+            ]"#,
+        ))
+        .expect("Decode patch");
+
+        patch.apply(&mut document).expect("Apply patch");
+
+        assert!(equal(
+            &document.0,
+            &json(r#"{ "name": "decoder_value", "tags": ["rust", "serde"], "language": "rust" }"#)
+                .0
+        ));
+    }
+
+    #[test]
+    fn it_applies_patches_atomically() {
+        let mut document = json(r#"{ "a": 1 }"#);
+        let original = document.clone();
+
+        let patch = Patch::from_iter([
+            Operation::Remove {
+                path: Pointer::parse("/a").unwrap(),
+            },
+            Operation::Remove {
+                path: Pointer::parse("/b").unwrap(),
+            },
+        ]);
+
+        assert!(patch.apply(&mut document).is_err());
+        assert_eq!(document, original);
+    }
+
+    #[test]
+    fn it_merges_and_diffs() {
+        let from = json(r#"{ "a": "b", "c": { "d": "e", "f": "g" }, "h": [1, 2, 3] }"#);
+        let to = json(r#"{ "a": "z", "c": { "d": "e" }, "h": [1, 4] }"#);
+
+        let mut merged = from.clone();
+        merge(&mut merged, &merge_diff(&from, &to));
+        assert!(equal(&merged.0, &to.0));
+
+        let mut patched = from.clone();
+        let patch = diff(&from, &to);
+        Patch::decode(patch.encode())
+            .expect("Decode patch")
+            .apply(&mut patched)
+            .expect("Apply patch");
+
+        assert!(equal(&patched.0, &to.0));
+    }
+
+    #[test]
+    fn it_compares_large_integers_exactly() {
+        let (a, b) = (
+            encode::u64(9_007_199_254_740_993),
+            encode::u64(9_007_199_254_740_992),
+        );
+
+        assert!(!equal(&a.0, &b.0));
+        assert!(equal(&encode::u64(3).0, &encode::f64(3.0).0));
+        assert_eq!(diff(&a, &b).operations.len(), 1);
+
+        let mut document = a.clone();
+        let test = Patch::from_iter([Operation::Test {
+            path: Pointer::root(),
+            value: b,
+        }]);
+        assert!(test.apply(&mut document).is_err());
+    }
+
+    #[test]
+    fn it_replaces_maps_with_non_string_keys() {
+        let map = |key| Value(Raw::Map(IndexMap::from([(Raw::U8(key), Raw::Bool(true))])));
+        let (from, to) = (map(1), map(2));
+
+        let patch = diff(&from, &to);
+        assert!(matches!(
+            patch.operations.as_slice(),
+            [Operation::Replace { path, .. }] if path.tokens().is_empty()
+        ));
+
+        let mut patched = from;
+        patch.apply(&mut patched).expect("Apply patch");
+        assert!(equal(&patched.0, &to.0));
+    }
+
+    #[test]
+    fn it_parses_pointers() {
+        let pointer = Pointer::parse("/a~1b/m~0n/0").expect("Parse pointer");
+
+        assert_eq!(pointer.tokens(), ["a/b", "m~n", "0"]);
+        assert_eq!(pointer.to_string(), "/a~1b/m~0n/0");
+        assert!(Pointer::parse("a").is_err());
+    }
+}
@@ -0,0 +1,412 @@
+//! Parse and render URL query strings and `application/x-www-form-urlencoded` data.
+//!
+//! Keys may use brackets to describe nested maps (`filter[status]=open`) and
+//! sequences (`tags[]=a&tags[]=b` or `items[0][name]=a`). Repeated keys also become
+//! sequences (`tag=a&tag=b`). All the leaves are strings.
+//!
+//! ```rust
+//! use decoder::decode::{lenient, map, sequence, string, u32};
+//! use decoder::query;
+//!
+//! let query = query::parse("?page=2&filter[status]=open&tags[]=a&tags[]=b")
+//!     .expect("Parse query");
+//! let mut query = map(query).expect("Query is a map");
+//!
+//! assert_eq!(query.required("page", lenient(u32)).unwrap(), 2);
+//! assert_eq!(
+//!     query
+//!         .required("filter", |filter| map(filter)?.required("status", string))
+//!         .unwrap(),
+//!     "open"
+//! );
+//! assert_eq!(
+//!     query.required("tags", sequence::<_, Vec<_>>(string)).unwrap(),
+//!     ["a", "b"]
+//! );
+//! ```
+use crate::value::{Raw, inner, scalar};
+use crate::{Error, Result, Value};
+
+use indexmap::IndexMap;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Parses a query string into a [`Value`] map.
+///
+/// A leading `?` is ignored, and so are parameters with an empty name. Malformed
+/// percent-encoded sequences are kept as they are. Sequence indices may be sparse
+/// or out of order (`items[1]=b&items[0]=a`); items are sorted by index.
+///
+/// Fails if a parameter conflicts with the shape given by a previous one
+/// (e.g. `filter[status]=open&filter=closed`).
+pub fn parse(query: &str) -> Result<Value> {
+    let query = query.strip_prefix('?').unwrap_or(query);
+    let mut root = Node::Map(IndexMap::new());
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = decode(key);
+
+        let (name, mut rest) = match key.find('[') {
+            Some(start) if start > 0 => key.split_at(start),
+            _ => (key.as_str(), ""),
+        };
+
+        if name.is_empty() {
+            continue;
+        }
+
+        let mut segments = vec![name];
+
+        while let Some(segment) = rest.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+            segments.push(segment.0);
+            rest = segment.1;
+        }
+
+        root.insert(&segments, decode(value)).map_err(|Conflict| {
+            Error::custom(format!(
+                "query parameter `{key}` conflicts with a previous one"
+            ))
+        })?;
+    }
+
+    Ok(Value(root.into_raw()))
+}
+
+/// Renders a [`Value`] map as a query string.
+///
+/// Nested maps and sequences use brackets. Empty maps and sequences, as well as
+/// `None` values, are omitted.
+pub fn to_string(value: &Value) -> Result<String> {
+    let Raw::Map(map) = inner(&value.0) else {
+        return Err(Error::InvalidType {
+            expected: "map".into(),
+            got: value.0.unexpected(),
+        });
+    };
+
+    let mut query = String::new();
+
+    for (key, value) in map {
+        render(&mut query, &scalar(key)?, value)?;
+    }
+
+    Ok(query)
+}
+
+enum Node {
+    Leaf(String),
+    Seq(BTreeMap<usize, Node>),
+    Map(IndexMap<String, Node>),
+}
+
+/// A parameter that does not fit the shape given by the previous ones.
+struct Conflict;
+
+impl Node {
+    fn new(segments: &[&str], value: String) -> std::result::Result<Self, Conflict> {
+        let mut node = match segments.first() {
+            None => return Ok(Node::Leaf(value)),
+            Some(segment) if segment.is_empty() || segment.parse::<usize>().is_ok() => {
+                Node::Seq(BTreeMap::new())
+            }
+            Some(_) => Node::Map(IndexMap::new()),
+        };
+
+        node.insert(segments, value)?;
+        Ok(node)
+    }
+
+    fn insert(&mut self, segments: &[&str], value: String) -> std::result::Result<(), Conflict> {
+        match segments.split_first() {
+            None => self.push(Node::Leaf(value)),
+            Some((&"", rest)) => self.push(Node::new(rest, value)?),
+            Some((segment, rest)) => match self {
+                Node::Seq(sequence) => {
+                    let index = segment.parse::<usize>().map_err(|_| Conflict)?;
+
+                    match sequence.get_mut(&index) {
+                        Some(node) => node.insert(rest, value),
+                        None => {
+                            let _ = sequence.insert(index, Node::new(rest, value)?);
+                            Ok(())
+                        }
+                    }
+                }
+                Node::Map(map) => match map.get_mut(*segment) {
+                    Some(node) => node.insert(rest, value),
+                    None => {
+                        let _ = map.insert((*segment).to_owned(), Node::new(rest, value)?);
+                        Ok(())
+                    }
+                },
+                Node::Leaf(_) => Err(Conflict),
+            },
+        }
+    }
+
+    /// Appends a node to a sequence, turning a leaf into a sequence first so that
+    /// repeated keys collect their values.
+    fn push(&mut self, node: Node) -> std::result::Result<(), Conflict> {
+        if let Node::Leaf(leaf) = self {
+            let leaf = std::mem::take(leaf);
+            *self = Node::Seq(BTreeMap::from([(0, Node::Leaf(leaf))]));
+        }
+
+        let Node::Seq(sequence) = self else {
+            return Err(Conflict);
+        };
+
+        let index = match sequence.last_key_value() {
+            Some((last, _)) => last.checked_add(1).ok_or(Conflict)?,
+            None => 0,
+        };
+
+        let _ = sequence.insert(index, node);
+        Ok(())
+    }
+
+    fn into_raw(self) -> Raw {
+        match self {
+            Node::Leaf(leaf) => Raw::String(leaf),
+            Node::Seq(sequence) => Raw::Seq(sequence.into_values().map(Node::into_raw).collect()),
+            Node::Map(map) => Raw::Map(
+                map.into_iter()
+                    .map(|(key, node)| (Raw::String(key), node.into_raw()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn render(query: &mut String, key: &str, value: &Raw) -> Result<()> {
+    match inner(value) {
+        Raw::Unit | Raw::Option(None) => {}
+        Raw::Map(map) => {
+            for (field, value) in map {
+                render(query, &format!("{key}[{}]", scalar(field)?), value)?;
+            }
+        }
+        Raw::Seq(sequence) => {
+            for (index, item) in sequence.iter().enumerate() {
+                match inner(item) {
+                    Raw::Map(_) | Raw::Seq(_) => render(query, &format!("{key}[{index}]"), item)?,
+                    _ => render(query, &format!("{key}[]"), item)?,
+                }
+            }
+        }
+        value => {
+            if !query.is_empty() {
+                query.push('&');
+            }
+
+            encode(query, key, true);
+            query.push('=');
+            encode(query, &scalar(value)?, false);
+        }
+    }
+
+    Ok(())
+}
+
+fn encode(output: &mut String, input: &str, is_key: bool) {
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                output.push(byte as char);
+            }
+            b'[' | b']' if is_key => output.push(byte as char),
+            b' ' => output.push('+'),
+            _ => {
+                let _ = write!(output, "%{byte:02X}");
+            }
+        }
+    }
+}
+
+fn decode(input: &str) -> String {
+    percent_decode(&input.replace('+', " "))
+}
+
+/// Decodes the percent-encoded sequences of the given input (e.g. `%20`), keeping
+/// malformed ones as they are.
+pub(crate) fn percent_decode(input: &str) -> String {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut input = input.bytes();
+
+    while let Some(byte) = input.next() {
+        match byte {
+            b'%' => {
+                let hex = input.clone().take(2).collect::<Vec<_>>();
+
+                match std::str::from_utf8(&hex)
+                    .ok()
+                    .filter(|hex| hex.len() == 2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(decoded) => {
+                        bytes.push(decoded);
+                        let _ = input.nth(1);
+                    }
+                    None => bytes.push(b'%'),
+                }
+            }
+            _ => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{lenient, map, sequence, string, u32};
+    use crate::encode;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Search {
+        page: u32,
+        status: Option<String>,
+        tags: Vec<String>,
+        items: Vec<String>,
+    }
+
+    impl Search {
+        fn decode(value: Value) -> Result<Self> {
+            let mut search = map(value)?;
+
+            Ok(Self {
+                page: search.required("page", lenient(u32))?,
+                status: search
+                    .optional("filter", |value| map(value)?.optional("status", string))?
+                    .flatten(),
+                tags: search.required("tags", sequence(string))?,
+                items: search.required(
+                    "items",
+                    sequence(|value| map(value)?.required("name", string)),
+                )?,
+            })
+        }
+
+        fn encode(&self) -> Value {
+            encode::map([
+                ("page", encode::u32(self.page)),
+                (
+                    "filter",
+                    encode::map([(
+                        "status",
+                        encode::optional(encode::string, self.status.as_deref()),
+                    )])
+                    .into(),
+                ),
+                ("tags", encode::sequence(encode::string, &self.tags)),
+                (
+                    "items",
+                    encode::sequence(
+                        |name| encode::map([("name", encode::string(name))]),
+                        &self.items,
+                    ),
+                ),
+            ])
+            .into()
+        }
+    }
+
+    #[test]
+    fn it_parses_queries() {
+        let search = Search::decode(
+            parse(
+                "?page=2&filter%5Bstatus%5D=open+now&tags[]=a&tags[]=b%26c&items[0][name]=x&items[1][name]=y",
+            )
+            .unwrap(),
+        )
+        .expect("Decode search");
+
+        assert_eq!(
+            search,
+            Search {
+                page: 2,
+                status: Some("open now".to_owned()),
+                tags: vec!["a".to_owned(), "b&c".to_owned()],
+                items: vec!["x".to_owned(), "y".to_owned()],
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_repeated_keys() {
+        let mut query = map(parse("tag=a&tag=b&tag=c&flag").unwrap()).expect("Decode map");
+
+        assert_eq!(
+            query
+                .required("tag", sequence::<_, Vec<_>>(string))
+                .unwrap(),
+            ["a", "b", "c"]
+        );
+        assert_eq!(query.required("flag", string).unwrap(), "");
+    }
+
+    #[test]
+    fn it_ignores_empty_names() {
+        let mut query = map(parse("a=1&=x&b=2").unwrap()).expect("Decode map");
+
+        assert_eq!(query.required("a", string).unwrap(), "1");
+        assert_eq!(query.required("b", string).unwrap(), "2");
+        assert!(query.finish().is_ok());
+    }
+
+    #[test]
+    fn it_parses_sparse_and_unordered_indices() {
+        let mut query = map(parse("items[1][a]=1&items[1][b]=2&tags[2]=y&tags[0]=x").unwrap())
+            .expect("Decode map");
+
+        let items = query
+            .required(
+                "items",
+                sequence::<_, Vec<_>>(|value| {
+                    let mut item = map(value)?;
+                    Ok((item.required("a", string)?, item.required("b", string)?))
+                }),
+            )
+            .unwrap();
+
+        assert_eq!(items, [("1".to_owned(), "2".to_owned())]);
+        assert_eq!(
+            query
+                .required("tags", sequence::<_, Vec<_>>(string))
+                .unwrap(),
+            ["x", "y"]
+        );
+    }
+
+    #[test]
+    fn it_rejects_conflicting_parameters() {
+        for query in [
+            "filter[status]=open&filter=closed",
+            "filter=closed&filter[status]=open",
+            "items[0]=a&items[name]=b",
+            "filter[status]=open&filter[]=closed",
+        ] {
+            let error = parse(query).expect_err(query);
+            assert!(error.to_string().contains("conflicts"), "{error}");
+        }
+
+        assert!(parse("tag=a&tag[]=b&tag=c").is_ok());
+    }
+
+    #[test]
+    fn it_renders_queries() {
+        let search = Search {
+            page: 1,
+            status: None,
+            tags: vec!["a b".to_owned()],
+            items: vec!["x".to_owned(), "y".to_owned()],
+        };
+
+        let query = to_string(&search.encode()).expect("Render query");
+
+        assert_eq!(query, "page=1&tags[]=a+b&items[0][name]=x&items[1][name]=y");
+        assert_eq!(Search::decode(parse(&query).unwrap()).unwrap(), search);
+    }
+}
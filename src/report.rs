@@ -0,0 +1,253 @@
+//! Turn errors into machine-readable reports.
+//!
+//! A [`Report`] can be encoded as a [`Value`] and, therefore, serialized in any
+//! format (e.g. as the body of an HTTP response):
+//!
+//! ```rust
+//! use decoder::decode::{map, string, u16};
+//! use decoder::report::Report;
+//!
+//! let input = r#"{ "name": "decoder", "port": 70000 }"#;
+//!
+//! let error = decoder::run(
+//!     serde_json::from_str,
+//!     |value| {
+//!         let mut server = map(value)?;
+//!
+//!         Ok((server.required("name", string)?, server.required("port", u16)?))
+//!     },
+//!     input,
+//! )
+//! .expect_err("Port is out of range");
+//!
+//! let report = Report::from(&error);
+//!
+//! assert_eq!(
+//!     serde_json::to_string(&report.encode()).unwrap(),
+//!     r#"[{"path":"/port","code":"out_of_range","expected":"u16","got":"integer `70000`","suggestion":null,"message":"out of range (expected: u16, got: Unsigned(70000))","origin":null}]"#
+//! );
+//! ```
+use crate::patch::Pointer;
+use crate::{Error, Segment, Unexpected, Value, encode};
+
+/// A machine-readable report of one or more [`Error`]s.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    entries: Vec<Entry>,
+}
+
+/// An entry of a [`Report`], describing a single [`Error`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The location of the invalid value.
+    ///
+    /// For missing and unknown fields, this is the location of the field itself.
+    pub path: Vec<Segment>,
+    /// The stable code of the [`Error`]. See [`Error::code`].
+    pub code: &'static str,
+    /// A description of what was expected, if known.
+    pub expected: Option<String>,
+    /// A description of what was found, if known.
+    pub got: Option<String>,
+    /// A "did you mean" suggestion, if any. See [`Error::suggestion`].
+    pub suggestion: Option<String>,
+    /// A human-readable message.
+    pub message: String,
+    /// The origin of the invalid value, if known. See [`Error::origin`].
+    pub origin: Option<String>,
+}
+
+impl Report {
+    /// Creates a new empty [`Report`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an [`Error`] to the [`Report`].
+    pub fn push(&mut self, error: &Error) {
+        self.entries.push(Entry::from(error));
+    }
+
+    /// Returns the entries of the [`Report`].
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns true if the [`Report`] has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Encodes the [`Report`] as a sequence of entries.
+    pub fn encode(&self) -> Value {
+        encode::sequence(Entry::encode, &self.entries)
+    }
+}
+
+impl Entry {
+    /// Encodes the [`Entry`] as a map with the `path`, `code`, `expected`, `got`,
+    /// `suggestion`, `message`, and `origin` fields.
+    ///
+    /// The `path` is encoded as a JSON Pointer (e.g. `/servers/0/port`).
+    pub fn encode(&self) -> Value {
+        use encode::{map, optional, string};
+
+        map([
+            ("path", self.pointer().encode()),
+            ("code", string(self.code)),
+            ("expected", optional(string, self.expected.as_deref())),
+            ("got", optional(string, self.got.as_deref())),
+            ("suggestion", optional(string, self.suggestion.as_deref())),
+            ("message", string(&self.message)),
+            ("origin", optional(string, self.origin.as_deref())),
+        ])
+        .into()
+    }
+
+    /// Returns the path of the [`Entry`] as a JSON [`Pointer`].
+    pub fn pointer(&self) -> Pointer {
+        self.path
+            .iter()
+            .fold(Pointer::root(), |pointer, segment| match segment {
+                Segment::Key(key) => pointer.push(key),
+                Segment::Index(index) => pointer.push(index.to_string()),
+            })
+    }
+}
+
+impl From<&Error> for Entry {
+    fn from(error: &Error) -> Self {
+        let mut path = error.path();
+        let root = error.root();
+
+        let (expected, got) = match root {
+            Error::InvalidType { expected, got }
+            | Error::InvalidValue { expected, got }
+            | Error::OutOfRange { expected, got } => {
+                (Some(expected.to_string()), Some(describe(got)))
+            }
+            Error::InvalidLength { expected, got } => {
+                (Some(expected.to_string()), Some(got.to_string()))
+            }
+            Error::UnknownVariant {
+                variant, expected, ..
+            } => (Some(expected.join(", ")), Some(variant.clone())),
+            Error::UnknownField {
+                field, expected, ..
+            } => {
+                path.push(Segment::Key(field.clone()));
+
+                (Some(expected.join(", ")), Some(field.clone()))
+            }
+            Error::FieldMissing { key, aliases, .. } => {
+                path.push(Segment::Key(key.clone()));
+
+                let mut keys = vec![key.clone()];
+                keys.extend(aliases.iter().cloned());
+
+                (Some(keys.join(", ")), None)
+            }
+            _ => (None, None),
+        };
+
+        Self {
+            path,
+            code: error.code(),
+            expected,
+            got,
+            suggestion: error.suggestion().map(str::to_owned),
+            message: root.to_string(),
+            origin: error.origin_of().map(str::to_owned),
+        }
+    }
+}
+
+impl From<&Error> for Report {
+    fn from(error: &Error) -> Self {
+        Self {
+            entries: vec![Entry::from(error)],
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a Error> for Report {
+    fn from_iter<T: IntoIterator<Item = &'a Error>>(errors: T) -> Self {
+        Self {
+            entries: errors.into_iter().map(Entry::from).collect(),
+        }
+    }
+}
+
+fn describe(unexpected: &Unexpected) -> String {
+    unexpected.to_unexpected().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{map, sequence, string, u16};
+    use crate::env::Env;
+    use crate::{Decoder, Result};
+
+    fn decode(value: Value) -> Result<Vec<(String, u16)>> {
+        sequence(|value| {
+            let mut server = map(value)?;
+
+            Ok((
+                server.required("host", string)?,
+                server.required("port", u16)?,
+            ))
+        })
+        .run(value)
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        let invalid: Value =
+            serde_json::from_str(r#"[{ "host": "a", "port": 1 }, { "port": "b" }]"#)
+                .expect("Deserialize JSON");
+
+        let missing = decode(invalid.clone()).expect_err("Host is missing");
+        let invalid = sequence(|value| map(value)?.required("port", u16))
+            .run(invalid)
+            .map(|_: Vec<u16>| ())
+            .expect_err("Port is invalid");
+
+        let report: Report = [&missing, &invalid].into_iter().collect();
+
+        assert_eq!(report.entries().len(), 2);
+
+        let missing = &report.entries()[0];
+        assert_eq!(missing.pointer().to_string(), "/1/host");
+        assert_eq!(missing.code, "missing_field");
+        assert_eq!(missing.suggestion.as_deref(), Some("port"));
+
+        let invalid = &report.entries()[1];
+        assert_eq!(invalid.pointer().to_string(), "/1/port");
+        assert_eq!(invalid.code, "invalid_type");
+        assert_eq!(invalid.expected.as_deref(), Some("u16"));
+        assert_eq!(invalid.got.as_deref(), Some("string \"b\""));
+
+        let encoded = serde_json::to_value(report.encode()).expect("Serialize report");
+        assert_eq!(encoded[1]["path"], "/1/port");
+        assert_eq!(encoded[1]["code"], "invalid_type");
+    }
+
+    #[test]
+    fn it_reports_origins() {
+        let env = Env::from_vars("APP", "_", [("APP_PORT", "http")]).expect("Load variables");
+
+        let error = env
+            .run(|value| map(value)?.required("port", u16))
+            .expect_err("Port is invalid");
+
+        let report = Report::from(&error);
+        let entry = &report.entries()[0];
+
+        assert_eq!(entry.pointer().to_string(), "/port");
+        assert_eq!(
+            entry.origin.as_deref(),
+            Some("environment variable APP_PORT")
+        );
+    }
+}
@@ -0,0 +1,855 @@
+//! Describe decoders and generate JSON Schemas from them.
+//!
+//! A [`Codec`] is a [`Decoder`] that also exposes a [`Schema`] describing the
+//! values it accepts. Codecs are built out of the same decoders available in
+//! [`decode`], so the schema can never drift from the decoding logic:
+//!
+//! ```rust
+//! use decoder::schema::{self, Codec};
+//!
+//! struct Person {
+//!     name: String,
+//!     age: Option<u8>,
+//!     tags: Vec<String>,
+//! }
+//!
+//! impl Person {
+//!     fn codec() -> Codec<Self> {
+//!         use decoder::schema::{object, optional, required, sequence, string, u8};
+//!
+//!         object((
+//!             required("name", string()),
+//!             optional("age", u8()),
+//!             required("tags", sequence(string())),
+//!         ))
+//!         .map(|(name, age, tags)| Person { name, age, tags })
+//!     }
+//! }
+//!
+//! let person = decoder::run(
+//!     serde_json::from_str,
+//!     Person::codec(),
+//!     r#"{ "name": "Ferris", "tags": ["crab"] }"#,
+//! )
+//! .expect("Decode person");
+//!
+//! assert_eq!(person.name, "Ferris");
+//!
+//! let schema = schema::json_schema(&Person::codec());
+//!
+//! assert_eq!(
+//!     serde_json::to_value(&schema).unwrap()["required"],
+//!     serde_json::json!(["name", "tags"])
+//! );
+//! ```
+use crate::decode;
+use crate::encode;
+use crate::validate::Describe;
+use crate::{Decoder, Error, Map, Result, Value};
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+mod validate;
+
+pub use validate::validate;
+
+/// The JSON Schema dialect produced by [`json_schema`].
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Generates a JSON Schema (draft 2020-12) for the values accepted by the
+/// given [`Codec`].
+pub fn json_schema<T>(codec: &Codec<T>) -> Value {
+    codec.schema.to_map().tag("$schema", DIALECT).into_value()
+}
+
+/// A description of the values accepted by a [`Codec`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// Any value.
+    Any,
+    /// A null value.
+    Null,
+    /// A boolean.
+    Boolean,
+    /// An integer in the given inclusive range.
+    Integer {
+        /// The minimum value, if any.
+        minimum: Option<i128>,
+        /// The maximum value, if any.
+        maximum: Option<i128>,
+    },
+    /// A number in the given inclusive range.
+    Number {
+        /// The minimum value, if any.
+        minimum: Option<f64>,
+        /// The maximum value, if any.
+        maximum: Option<f64>,
+    },
+    /// A string.
+    String,
+    /// A specific string.
+    Const(String),
+    /// One of the given strings.
+    Enum(Vec<String>),
+    /// A value of the given [`Schema`] or null.
+    Nullable(Box<Schema>),
+    /// A sequence of values of the given [`Schema`].
+    Array(Box<Schema>),
+    /// A map with the given properties.
+    Object {
+        /// The properties of the map.
+        properties: Vec<Property>,
+        /// Whether properties other than the given ones are allowed.
+        additional: bool,
+    },
+    /// A map whose `tag` field selects the [`Schema`] of the rest of the map.
+    Tagged {
+        /// The name of the tag field.
+        tag: String,
+        /// The name and [`Schema`] of each variant.
+        variants: Vec<(String, Schema)>,
+    },
+}
+
+/// A property of a [`Schema::Object`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    /// The name of the property.
+    pub name: String,
+    /// The [`Schema`] of the property.
+    pub schema: Schema,
+    /// Whether the property is required.
+    pub required: bool,
+}
+
+impl Schema {
+    /// Encodes the [`Schema`] as a JSON Schema.
+    pub fn encode(&self) -> Value {
+        self.to_map().into_value()
+    }
+
+    fn to_map(&self) -> Map {
+        use encode::{bool, map, sequence, string};
+
+        match self {
+            Self::Any => Map::new(),
+            Self::Null => map([("type", string("null"))]),
+            Self::Boolean => map([("type", string("boolean"))]),
+            Self::Integer { minimum, maximum } => map([("type", string("integer"))]
+                .into_iter()
+                .chain(minimum.map(|minimum| ("minimum", integer(minimum))))
+                .chain(maximum.map(|maximum| ("maximum", integer(maximum))))),
+            Self::Number { minimum, maximum } => map([("type", string("number"))]
+                .into_iter()
+                .chain(minimum.map(|minimum| ("minimum", encode::f64(minimum))))
+                .chain(maximum.map(|maximum| ("maximum", encode::f64(maximum))))),
+            Self::String => map([("type", string("string"))]),
+            Self::Const(value) => map([("const", string(value))]),
+            Self::Enum(values) => map([("enum", sequence(string, values))]),
+            Self::Nullable(schema) => map([(
+                "anyOf",
+                sequence(Self::encode, [schema.as_ref(), &Self::Null]),
+            )]),
+            Self::Array(items) => map([("type", string("array")), ("items", items.encode())]),
+            Self::Object {
+                properties,
+                additional,
+            } => {
+                let required = properties
+                    .iter()
+                    .filter(|property| property.required)
+                    .map(|property| property.name.as_str());
+
+                let object = map([
+                    ("type", string("object")),
+                    (
+                        "properties",
+                        map(properties
+                            .iter()
+                            .map(|property| (property.name.as_str(), property.schema.encode())))
+                        .into(),
+                    ),
+                    ("required", sequence(string, required)),
+                ]);
+
+                if *additional {
+                    object
+                } else {
+                    object.extend(map([("additionalProperties", bool(false))]))
+                }
+            }
+            Self::Tagged { tag, variants } => map([(
+                "oneOf",
+                sequence(|(name, schema)| variant(tag, name, schema), variants),
+            )]),
+        }
+    }
+}
+
+fn integer(n: i128) -> Value {
+    if let Ok(n) = u64::try_from(n) {
+        encode::u64(n)
+    } else if let Ok(n) = i64::try_from(n) {
+        encode::i64(n)
+    } else {
+        encode::f64(n as f64)
+    }
+}
+
+fn variant(tag: &str, name: &str, schema: &Schema) -> Value {
+    let tag = Property {
+        name: tag.to_owned(),
+        schema: Schema::Const(name.to_owned()),
+        required: true,
+    };
+
+    match schema {
+        Schema::Object {
+            properties,
+            additional,
+        } => Schema::Object {
+            properties: std::iter::once(tag)
+                .chain(properties.iter().cloned())
+                .collect(),
+            additional: *additional,
+        }
+        .encode(),
+        schema => encode::map([(
+            "allOf",
+            encode::sequence(
+                Schema::encode,
+                [
+                    &Schema::Object {
+                        properties: vec![tag],
+                        additional: true,
+                    },
+                    schema,
+                ],
+            ),
+        )])
+        .into(),
+    }
+}
+
+/// A [`Decoder`] that describes the values it accepts with a [`Schema`].
+pub struct Codec<T> {
+    decoder: Arc<dyn Fn(Value) -> Result<T> + Send + Sync>,
+    schema: Schema,
+}
+
+impl<T> Codec<T> {
+    /// Creates a new [`Codec`] from a [`Decoder`] and the [`Schema`] of the values
+    /// it accepts.
+    pub fn new(decoder: impl Decoder<Output = T> + Send + Sync + 'static, schema: Schema) -> Self {
+        Self {
+            decoder: Arc::new(move |value| decoder.run(value)),
+            schema,
+        }
+    }
+
+    /// Returns the [`Schema`] of the [`Codec`].
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Transforms the output of the [`Codec`] with the given function.
+    pub fn map<U>(self, f: impl Fn(T) -> U + Send + Sync + 'static) -> Codec<U>
+    where
+        T: 'static,
+    {
+        let decoder = self.decoder;
+
+        Codec {
+            decoder: Arc::new(move |value| decoder(value).map(&f)),
+            schema: self.schema,
+        }
+    }
+}
+
+impl<T: Numeric> Codec<T> {
+    /// Restricts the values accepted by the [`Codec`] to the given range.
+    ///
+    /// The range is also applied to the `minimum` and `maximum` of its
+    /// [`Schema`]; exclusive bounds become the closest inclusive ones.
+    pub fn range(self, range: impl RangeBounds<T> + Send + Sync + 'static) -> Self {
+        let mut schema = self.schema.clone();
+        T::restrict(&mut schema, range.start_bound(), range.end_bound());
+
+        let expected = crate::validate::describe_range(&range);
+
+        Codec::new(
+            crate::validate::ensure(self, expected.into(), move |output| range.contains(output)),
+            schema,
+        )
+    }
+}
+
+impl<T> Decoder for Codec<T> {
+    type Output = T;
+
+    fn run(&self, value: Value) -> Result<T> {
+        (self.decoder)(value)
+    }
+}
+
+impl<T> Clone for Codec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            decoder: self.decoder.clone(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Codec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Codec")
+            .field("schema", &self.schema)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Codec`] of any [`Value`].
+pub fn value() -> Codec<Value> {
+    Codec::new(Ok, Schema::Any)
+}
+
+/// A [`Codec`] of a `bool`.
+pub fn bool() -> Codec<bool> {
+    Codec::new(decode::bool, Schema::Boolean)
+}
+
+/// A [`Codec`] of a `String`.
+pub fn string() -> Codec<String> {
+    Codec::new(decode::string, Schema::String)
+}
+
+macro_rules! integers {
+    ($($type:ident),*) => {
+        $(
+            #[doc = concat!("A [`Codec`] of a `", stringify!($type), "`.")]
+            pub fn $type() -> Codec<$type> {
+                Codec::new(
+                    decode::$type,
+                    Schema::Integer {
+                        minimum: Some($type::MIN.into()),
+                        maximum: Some($type::MAX.into()),
+                    },
+                )
+            }
+        )*
+    };
+}
+
+integers!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// A number that can bound the [`Schema`] of a [`Codec`].
+///
+/// See [`Codec::range`].
+pub trait Numeric: PartialOrd + fmt::Debug + Describe + Send + Sync + 'static {
+    /// Narrows the bounds of the given [`Schema`] to the given ones.
+    fn restrict(schema: &mut Schema, start: Bound<&Self>, end: Bound<&Self>);
+}
+
+macro_rules! numeric_integers {
+    ($($type:ident),*) => {
+        $(
+            impl Numeric for $type {
+                fn restrict(schema: &mut Schema, start: Bound<&Self>, end: Bound<&Self>) {
+                    let Schema::Integer { minimum, maximum } = schema else {
+                        return;
+                    };
+
+                    let start = match start {
+                        Bound::Included(n) => Some(i128::from(*n)),
+                        Bound::Excluded(n) => Some(i128::from(*n) + 1),
+                        Bound::Unbounded => None,
+                    };
+
+                    let end = match end {
+                        Bound::Included(n) => Some(i128::from(*n)),
+                        Bound::Excluded(n) => Some(i128::from(*n) - 1),
+                        Bound::Unbounded => None,
+                    };
+
+                    *minimum = (*minimum).max(start);
+                    *maximum = match (*maximum, end) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    };
+                }
+            }
+        )*
+    };
+}
+
+numeric_integers!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+macro_rules! numeric_floats {
+    ($($type:ident),*) => {
+        $(
+            impl Numeric for $type {
+                fn restrict(schema: &mut Schema, start: Bound<&Self>, end: Bound<&Self>) {
+                    let Schema::Number { minimum, maximum } = schema else {
+                        return;
+                    };
+
+                    let start = match start {
+                        Bound::Included(n) => Some(f64::from(*n)),
+                        Bound::Excluded(n) => Some(f64::from(n.next_up())),
+                        Bound::Unbounded => None,
+                    };
+
+                    let end = match end {
+                        Bound::Included(n) => Some(f64::from(*n)),
+                        Bound::Excluded(n) => Some(f64::from(n.next_down())),
+                        Bound::Unbounded => None,
+                    };
+
+                    *minimum = match (*minimum, start) {
+                        (Some(a), Some(b)) => Some(a.max(b)),
+                        (a, b) => a.or(b),
+                    };
+                    *maximum = match (*maximum, end) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    };
+                }
+            }
+        )*
+    };
+}
+
+numeric_floats!(f32, f64);
+
+/// A [`Codec`] of an `f32`.
+pub fn f32() -> Codec<f32> {
+    Codec::new(
+        decode::f32,
+        Schema::Number {
+            minimum: None,
+            maximum: None,
+        },
+    )
+}
+
+/// A [`Codec`] of an `f64`.
+pub fn f64() -> Codec<f64> {
+    Codec::new(
+        decode::f64,
+        Schema::Number {
+            minimum: None,
+            maximum: None,
+        },
+    )
+}
+
+/// Takes a [`Codec`] of a certain type `T` and produces a new [`Codec`] of
+/// `Option<T>` that also accepts null.
+pub fn nullable<T: 'static>(codec: Codec<T>) -> Codec<Option<T>> {
+    let schema = Schema::Nullable(Box::new(codec.schema.clone()));
+
+    Codec::new(decode::optional(codec), schema)
+}
+
+/// Takes a [`Codec`] of a certain type `T` and produces a new [`Codec`] of
+/// a sequence of `T` values.
+pub fn sequence<T: 'static>(codec: Codec<T>) -> Codec<Vec<T>> {
+    let schema = Schema::Array(Box::new(codec.schema.clone()));
+
+    Codec::new(decode::sequence(codec), schema)
+}
+
+/// A [`Codec`] of one of the given strings, each mapped to a value.
+pub fn enumeration<'a, T>(variants: impl IntoIterator<Item = (&'a str, T)>) -> Codec<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let variants: Vec<(String, T)> = variants
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect();
+
+    let schema = Schema::Enum(variants.iter().map(|(name, _)| name.clone()).collect());
+
+    Codec::new(
+        move |value| {
+            let name = decode::string(value)?;
+
+            variants
+                .iter()
+                .find(|(variant, _)| *variant == name)
+                .map(|(_, value)| value.clone())
+                .ok_or_else(|| Error::unknown_variant(&name, &names(&variants)))
+        },
+        schema,
+    )
+}
+
+/// A [`Codec`] of a map whose `tag` field selects the [`Codec`] used to decode
+/// the rest of the map.
+pub fn tagged<'a, T: 'static>(
+    tag: &str,
+    variants: impl IntoIterator<Item = (&'a str, Codec<T>)>,
+) -> Codec<T> {
+    let tag = tag.to_owned();
+    let variants: Vec<(String, Codec<T>)> = variants
+        .into_iter()
+        .map(|(name, codec)| (name.to_owned(), codec))
+        .collect();
+
+    let schema = Schema::Tagged {
+        tag: tag.clone(),
+        variants: variants
+            .iter()
+            .map(|(name, codec)| (name.clone(), codec.schema.clone()))
+            .collect(),
+    };
+
+    Codec::new(
+        move |value| {
+            let mut map = decode::map(value)?;
+            let name = map.required(&tag, decode::string)?;
+
+            let Some((_, codec)) = variants.iter().find(|(variant, _)| *variant == name) else {
+                return Err(Error::unknown_variant(&name, &names(&variants)).field(&tag));
+            };
+
+            codec.run(map.into_value())
+        },
+        schema,
+    )
+}
+
+fn names<T>(variants: &[(String, T)]) -> Vec<&str> {
+    variants.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+/// A [`Codec`] of a map with the given [`Fields`], allowing any other fields.
+pub fn object<F>(fields: F) -> Codec<F::Output>
+where
+    F: Fields + Send + Sync + 'static,
+{
+    build(fields, true)
+}
+
+/// A [`Codec`] of a map with the given [`Fields`], rejecting any other fields.
+///
+/// See [`Map::finish`].
+pub fn strict_object<F>(fields: F) -> Codec<F::Output>
+where
+    F: Fields + Send + Sync + 'static,
+{
+    build(fields, false)
+}
+
+fn build<F>(fields: F, additional: bool) -> Codec<F::Output>
+where
+    F: Fields + Send + Sync + 'static,
+{
+    let mut properties = Vec::new();
+    fields.describe(&mut properties);
+
+    Codec::new(
+        move |value| {
+            if additional {
+                return fields.decode(&mut decode::map(value)?);
+            }
+
+            let mut map = decode::map(value)?.strict();
+            let output = fields.decode(&mut map)?;
+
+            map.finish()?;
+
+            Ok(output)
+        },
+        Schema::Object {
+            properties,
+            additional,
+        },
+    )
+}
+
+/// Some fields of a map, decoded together.
+///
+/// It is implemented by [`FieldCodec`] and tuples of [`Fields`]; nesting tuples
+/// flattens their fields into the same map.
+pub trait Fields {
+    /// The output of decoding the [`Fields`].
+    type Output;
+
+    /// Adds the [`Property`] of every field to the given list.
+    fn describe(&self, properties: &mut Vec<Property>);
+
+    /// Decodes the [`Fields`] from the given [`Map`].
+    fn decode(&self, map: &mut Map) -> Result<Self::Output>;
+}
+
+/// A [`Codec`] of a single field of a map.
+///
+/// See [`required`] and [`optional`].
+pub struct FieldCodec<T> {
+    property: Property,
+    decoder: Arc<dyn Fn(&mut Map) -> Result<T> + Send + Sync>,
+}
+
+impl<T> fmt::Debug for FieldCodec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldCodec")
+            .field("property", &self.property)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Fields for FieldCodec<T> {
+    type Output = T;
+
+    fn describe(&self, properties: &mut Vec<Property>) {
+        properties.push(self.property.clone());
+    }
+
+    fn decode(&self, map: &mut Map) -> Result<T> {
+        (self.decoder)(map)
+    }
+}
+
+/// A required field of a map. See [`Map::required`].
+pub fn required<T: 'static>(name: &str, codec: Codec<T>) -> FieldCodec<T> {
+    let key = name.to_owned();
+
+    FieldCodec {
+        property: Property {
+            name: name.to_owned(),
+            schema: codec.schema.clone(),
+            required: true,
+        },
+        decoder: Arc::new(move |map| map.required(&key, |value| codec.run(value))),
+    }
+}
+
+/// An optional field of a map. See [`Map::optional`].
+///
+/// Since a null field is decoded as missing, the [`Schema`] of the field also
+/// accepts null.
+pub fn optional<T: 'static>(name: &str, codec: Codec<T>) -> FieldCodec<Option<T>> {
+    let key = name.to_owned();
+
+    let schema = match &codec.schema {
+        Schema::Any | Schema::Null | Schema::Nullable(_) => codec.schema.clone(),
+        schema => Schema::Nullable(Box::new(schema.clone())),
+    };
+
+    FieldCodec {
+        property: Property {
+            name: name.to_owned(),
+            schema,
+            required: false,
+        },
+        decoder: Arc::new(move |map| map.optional(&key, |value| codec.run(value))),
+    }
+}
+
+macro_rules! fields {
+    ($($field:ident),+) => {
+        impl<$($field: Fields),+> Fields for ($($field,)+) {
+            type Output = ($($field::Output,)+);
+
+            #[allow(non_snake_case)]
+            fn describe(&self, properties: &mut Vec<Property>) {
+                let ($($field,)+) = self;
+                $($field.describe(properties);)+
+            }
+
+            #[allow(non_snake_case)]
+            fn decode(&self, map: &mut Map) -> Result<Self::Output> {
+                let ($($field,)+) = self;
+                Ok(($($field.decode(map)?,)+))
+            }
+        }
+    };
+}
+
+fields!(A);
+fields!(A, B);
+fields!(A, B, C);
+fields!(A, B, C, D);
+fields!(A, B, C, D, E);
+fields!(A, B, C, D, E, F);
+fields!(A, B, C, D, E, F, G);
+fields!(A, B, C, D, E, F, G, H);
+fields!(A, B, C, D, E, F, G, H, I);
+fields!(A, B, C, D, E, F, G, H, I, J);
+fields!(A, B, C, D, E, F, G, H, I, J, K);
+fields!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square { side: f64, unit: Unit },
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Unit {
+        Meters,
+        Feet,
+    }
+
+    fn shape() -> Codec<Shape> {
+        tagged(
+            "type",
+            [
+                (
+                    "circle",
+                    strict_object((required("radius", f64()),))
+                        .map(|(radius,)| Shape::Circle { radius }),
+                ),
+                (
+                    "square",
+                    object((
+                        required("side", f64()),
+                        required(
+                            "unit",
+                            enumeration([("m", Unit::Meters), ("ft", Unit::Feet)]),
+                        ),
+                    ))
+                    .map(|(side, unit)| Shape::Square { side, unit }),
+                ),
+            ],
+        )
+    }
+
+    fn drawing() -> Codec<(String, Option<u8>, Vec<Shape>)> {
+        object((
+            required("name", string()),
+            optional("layer", u8()),
+            required("shapes", sequence(shape())),
+        ))
+    }
+
+    #[test]
+    fn it_decodes_with_codecs() {
+        let value: Value = serde_json::from_value(json!({
+            "name": "sketch",
+            "shapes": [
+                { "type": "circle", "radius": 1.5 },
+                { "type": "square", "side": 2.0, "unit": "ft" },
+            ],
+        }))
+        .expect("Deserialize JSON");
+
+        let (name, layer, shapes) = drawing().run(value).expect("Decode drawing");
+
+        assert_eq!(name, "sketch");
+        assert_eq!(layer, None);
+        assert_eq!(
+            shapes,
+            [
+                Shape::Circle { radius: 1.5 },
+                Shape::Square {
+                    side: 2.0,
+                    unit: Unit::Feet
+                }
+            ]
+        );
+
+        let value: Value =
+            serde_json::from_value(json!({ "type": "triangle" })).expect("Deserialize JSON");
+        let error = shape().run(value).expect_err("Triangle is unknown");
+        assert_eq!(error.code(), "unknown_variant");
+
+        let value: Value =
+            serde_json::from_value(json!({ "type": "circle", "radius": 1, "side": 2 }))
+                .expect("Deserialize JSON");
+        let error = shape().run(value).expect_err("Side is unknown");
+        assert_eq!(error.code(), "unknown_field");
+    }
+
+    #[test]
+    fn it_generates_json_schemas() {
+        let schema = serde_json::to_value(json_schema(&drawing())).expect("Serialize schema");
+
+        assert_eq!(
+            schema,
+            json!({
+                "$schema": DIALECT,
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "layer": {
+                        "anyOf": [
+                            { "type": "integer", "minimum": 0, "maximum": 255 },
+                            { "type": "null" },
+                        ],
+                    },
+                    "shapes": {
+                        "type": "array",
+                        "items": {
+                            "oneOf": [
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "type": { "const": "circle" },
+                                        "radius": { "type": "number" },
+                                    },
+                                    "required": ["type", "radius"],
+                                    "additionalProperties": false,
+                                },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "type": { "const": "square" },
+                                        "side": { "type": "number" },
+                                        "unit": { "enum": ["m", "ft"] },
+                                    },
+                                    "required": ["type", "side", "unit"],
+                                },
+                            ],
+                        },
+                    },
+                },
+                "required": ["name", "shapes"],
+            })
+        );
+    }
+
+    #[test]
+    fn it_keeps_schemas_in_sync_with_decoders() {
+        let person = object((
+            optional("age", u8().range(1..=150)),
+            required("ratio", f64().range(0.0..1.0)),
+        ));
+
+        for (input, is_valid) in [
+            (json!({ "age": null, "ratio": 0.5 }), true),
+            (json!({ "age": 30, "ratio": 0.0 }), true),
+            (json!({ "age": 0, "ratio": 0.5 }), false),
+            (json!({ "age": 151, "ratio": 0.5 }), false),
+            (json!({ "ratio": 1.0 }), false),
+        ] {
+            let value: Value = serde_json::from_value(input).expect("Deserialize JSON");
+
+            assert_eq!(person.run(value.clone()).is_ok(), is_valid);
+            assert_eq!(
+                validate(&json_schema(&person), &value).is_ok(),
+                is_valid,
+                "{value:?}"
+            );
+        }
+
+        assert_eq!(
+            u16().range(..1024).schema(),
+            &Schema::Integer {
+                minimum: Some(0),
+                maximum: Some(1023)
+            }
+        );
+    }
+}
@@ -0,0 +1,720 @@
+use crate::patch::Pointer;
+use crate::query::percent_decode;
+use crate::value::{Number, equal, number};
+use crate::value::{Raw, inner, scalar};
+use crate::{Error, Value};
+
+use indexmap::IndexMap;
+
+/// The maximum amount of nested schemas, which guards against cyclic references.
+const MAX_DEPTH: usize = 256;
+
+/// Validates a [`Value`] against a JSON Schema document (draft 2020-12).
+///
+/// The supported keywords are `type`, `const`, `enum`, `minimum`, `maximum`,
+/// `exclusiveMinimum`, `exclusiveMaximum`, `multipleOf`, `minLength`, `maxLength`,
+/// `pattern` (with the `regex` feature), `items`, `prefixItems`, `minItems`,
+/// `maxItems`, `uniqueItems`, `properties`, `patternProperties` (with the `regex`
+/// feature), `required`, `additionalProperties`, `allOf`, `anyOf`, `oneOf`, `not`,
+/// and `$ref` within the same document. Any other keyword is ignored. Without the
+/// `regex` feature, `additionalProperties` does not apply when `patternProperties`
+/// is present, since the keys it matches cannot be told apart.
+///
+/// Every violation is returned as an [`Error`] annotated with its location, which
+/// can be turned into a [`Report`](crate::report::Report).
+pub fn validate(schema: &Value, value: &Value) -> Result<(), Vec<Error>> {
+    let validator = Validator {
+        root: &schema.0,
+        #[cfg(feature = "regex")]
+        patterns: std::cell::RefCell::default(),
+    };
+
+    let errors = validator.check(&schema.0, &value.0, 0);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+struct Validator<'a> {
+    root: &'a Raw,
+    /// The compiled patterns of the schema, so each one is compiled once.
+    #[cfg(feature = "regex")]
+    patterns:
+        std::cell::RefCell<std::collections::HashMap<&'a str, Result<regex::Regex, regex::Error>>>,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&self, schema: &'a Raw, value: &Raw, depth: usize) -> Vec<Error> {
+        if depth > MAX_DEPTH {
+            return vec![Error::custom("invalid schema: too many nested schemas")];
+        }
+
+        let value = inner(value);
+
+        match inner(schema) {
+            Raw::Bool(true) => Vec::new(),
+            Raw::Bool(false) => vec![Error::InvalidValue {
+                expected: "no value".into(),
+                got: value.unexpected(),
+            }],
+            Raw::Map(schema) => self.check_map(schema, value, depth + 1),
+            _ => vec![Error::custom("invalid schema: expected a boolean or a map")],
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    fn regex(&self, pattern: &'a str) -> Result<regex::Regex, Error> {
+        self.patterns
+            .borrow_mut()
+            .entry(pattern)
+            .or_insert_with(|| regex::Regex::new(pattern))
+            .clone()
+            .map_err(|error| Error::custom(format!("invalid schema: {error}")))
+    }
+
+    #[cfg(feature = "regex")]
+    fn check_pattern(&self, pattern: &'a str, string: &str) -> Result<(), Error> {
+        if self.regex(pattern)?.is_match(string) {
+            Ok(())
+        } else {
+            Err(Error::InvalidValue {
+                expected: format!("a string matching `{pattern}`").into(),
+                got: crate::Unexpected::Str(string.to_owned()),
+            })
+        }
+    }
+
+    fn is_valid(&self, schema: &'a Raw, value: &Raw, depth: usize) -> bool {
+        self.check(schema, value, depth).is_empty()
+    }
+
+    fn check_map(&self, schema: &'a IndexMap<Raw, Raw>, value: &Raw, depth: usize) -> Vec<Error> {
+        let keyword = |name: &str| schema.get(&Raw::String(name.to_owned())).map(inner);
+        let mut errors = Vec::new();
+
+        if let Some(reference) = keyword("$ref") {
+            match self.resolve(reference) {
+                Ok(target) => errors.extend(self.check(target, value, depth)),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if let Some(types) = keyword("type") {
+            let types: Vec<&str> = match types {
+                Raw::String(name) => vec![name],
+                Raw::Seq(names) => names
+                    .iter()
+                    .filter_map(|name| match inner(name) {
+                        Raw::String(name) => Some(name.as_str()),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            if !types.iter().any(|name| is_type(name, value)) {
+                errors.push(Error::InvalidType {
+                    expected: types.join(" or ").into(),
+                    got: value.unexpected(),
+                });
+
+                return errors;
+            }
+        }
+
+        if let Some(constant) = keyword("const")
+            && !equal(constant, value)
+        {
+            errors.push(Error::InvalidValue {
+                expected: describe(constant).into(),
+                got: value.unexpected(),
+            });
+        }
+
+        if let Some(Raw::Seq(variants)) = keyword("enum")
+            && !variants.iter().any(|variant| equal(variant, value))
+        {
+            let variants: Vec<String> = variants.iter().map(describe).collect();
+
+            errors.push(Error::InvalidValue {
+                expected: format!("one of {}", variants.join(", ")).into(),
+                got: value.unexpected(),
+            });
+        }
+
+        if let Some(n) = number(value) {
+            let bounds: [(&str, &str, fn(Number, Number) -> bool); 4] = [
+                ("minimum", "at least", |n, bound| n >= bound),
+                ("maximum", "at most", |n, bound| n <= bound),
+                ("exclusiveMinimum", "greater than", |n, bound| n > bound),
+                ("exclusiveMaximum", "less than", |n, bound| n < bound),
+            ];
+
+            for (name, description, satisfies) in bounds {
+                let Some(bound) = keyword(name).and_then(number) else {
+                    continue;
+                };
+
+                if !satisfies(n, bound) {
+                    errors.push(Error::OutOfRange {
+                        expected: format!("{description} {bound}").into(),
+                        got: value.unexpected(),
+                    });
+                }
+            }
+
+            if let Some(factor) = keyword("multipleOf").and_then(number) {
+                if factor.as_f64() <= 0.0 {
+                    errors.push(Error::custom(
+                        "invalid schema: `multipleOf` must be greater than 0",
+                    ));
+                } else if !is_multiple(n, factor) {
+                    errors.push(Error::InvalidValue {
+                        expected: format!("a multiple of {factor}").into(),
+                        got: value.unexpected(),
+                    });
+                }
+            }
+        }
+
+        if let Raw::String(string) = value {
+            let length = string.chars().count();
+
+            check_length(
+                &mut errors,
+                length,
+                "characters",
+                &keyword,
+                "minLength",
+                "maxLength",
+            );
+
+            #[cfg(feature = "regex")]
+            if let Some(Raw::String(pattern)) = keyword("pattern")
+                && let Err(error) = self.check_pattern(pattern, string)
+            {
+                errors.push(error);
+            }
+        }
+
+        if let Raw::Seq(items) = value {
+            check_length(
+                &mut errors,
+                items.len(),
+                "items",
+                &keyword,
+                "minItems",
+                "maxItems",
+            );
+
+            let prefix = match keyword("prefixItems") {
+                Some(Raw::Seq(prefix)) => prefix.as_slice(),
+                _ => &[],
+            };
+
+            for (index, item) in items.iter().enumerate() {
+                let schema = prefix.get(index).or_else(|| keyword("items"));
+
+                if let Some(schema) = schema {
+                    errors.extend(
+                        self.check(schema, item, depth)
+                            .into_iter()
+                            .map(|error| error.item(index)),
+                    );
+                }
+            }
+
+            if let Some(Raw::Bool(true)) = keyword("uniqueItems") {
+                for (index, item) in items.iter().enumerate() {
+                    if items[..index].iter().any(|other| equal(other, item)) {
+                        errors.push(
+                            Error::InvalidValue {
+                                expected: "a unique item".into(),
+                                got: item.unexpected(),
+                            }
+                            .item(index),
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Raw::Map(fields) = value {
+            self.check_fields(schema, fields, depth, &mut errors);
+        }
+
+        if let Some(Raw::Seq(schemas)) = keyword("allOf") {
+            for schema in schemas {
+                errors.extend(self.check(schema, value, depth));
+            }
+        }
+
+        self.check_branches(schema, value, depth, &mut errors);
+
+        if let Some(schema) = keyword("not")
+            && self.is_valid(schema, value, depth)
+        {
+            errors.push(Error::InvalidValue {
+                expected: "a value not matching the schema".into(),
+                got: value.unexpected(),
+            });
+        }
+
+        errors
+    }
+
+    fn check_fields(
+        &self,
+        schema: &'a IndexMap<Raw, Raw>,
+        fields: &IndexMap<Raw, Raw>,
+        depth: usize,
+        errors: &mut Vec<Error>,
+    ) {
+        let keyword = |name: &str| schema.get(&Raw::String(name.to_owned())).map(inner);
+
+        let properties = match keyword("properties") {
+            Some(Raw::Map(properties)) => Some(properties),
+            _ => None,
+        };
+
+        if let Some(Raw::Seq(required)) = keyword("required") {
+            let available: Vec<String> = fields.keys().filter_map(|key| scalar(key).ok()).collect();
+
+            for key in required.iter().filter_map(|key| scalar(key).ok()) {
+                if !fields.contains_key(&Raw::String(key.clone())) {
+                    errors.push(Error::missing_field(&[&key], available.clone()));
+                }
+            }
+        }
+
+        let patterns = match keyword("patternProperties") {
+            Some(Raw::Map(patterns)) => Some(patterns),
+            _ => None,
+        };
+
+        for (key, field) in fields {
+            let Ok(name) = scalar(key) else {
+                continue;
+            };
+
+            let mut errors_of_field = Vec::new();
+            let mut is_additional = true;
+
+            if let Some(schema) = properties.and_then(|properties| properties.get(key)) {
+                errors_of_field.extend(self.check(schema, field, depth));
+                is_additional = false;
+            }
+
+            #[cfg(feature = "regex")]
+            for (pattern, schema) in patterns.into_iter().flatten() {
+                let Raw::String(pattern) = inner(pattern) else {
+                    continue;
+                };
+
+                match self.regex(pattern) {
+                    Ok(regex) if regex.is_match(&name) => {
+                        errors_of_field.extend(self.check(schema, field, depth));
+                        is_additional = false;
+                    }
+                    Ok(_) => {}
+                    Err(error) => errors.push(error),
+                }
+            }
+
+            #[cfg(not(feature = "regex"))]
+            if patterns.is_some() {
+                is_additional = false;
+            }
+
+            match keyword("additionalProperties") {
+                Some(Raw::Bool(false)) if is_additional => {
+                    let expected = properties
+                        .into_iter()
+                        .flat_map(IndexMap::keys)
+                        .filter_map(|key| scalar(key).ok())
+                        .collect();
+
+                    errors.push(Error::unknown_field_in(name, expected));
+                    continue;
+                }
+                Some(schema) if is_additional => {
+                    errors_of_field.extend(self.check(schema, field, depth));
+                }
+                _ => {}
+            }
+
+            errors.extend(errors_of_field.into_iter().map(|error| error.field(&name)));
+        }
+    }
+
+    fn check_branches(
+        &self,
+        schema: &'a IndexMap<Raw, Raw>,
+        value: &Raw,
+        depth: usize,
+        errors: &mut Vec<Error>,
+    ) {
+        let keyword = |name: &str| schema.get(&Raw::String(name.to_owned())).map(inner);
+
+        if let Some(Raw::Seq(schemas)) = keyword("anyOf") {
+            let branches: Vec<_> = schemas
+                .iter()
+                .map(|schema| self.check(schema, value, depth))
+                .collect();
+
+            if !branches.iter().any(Vec::is_empty) {
+                errors.push(Error::InvalidValue {
+                    expected: format!(
+                        "a value matching any of the schemas ({})",
+                        describe_branches(&branches)
+                    )
+                    .into(),
+                    got: value.unexpected(),
+                });
+            }
+        }
+
+        if let Some(Raw::Seq(schemas)) = keyword("oneOf") {
+            let branches: Vec<_> = schemas
+                .iter()
+                .map(|schema| self.check(schema, value, depth))
+                .collect();
+
+            let matches: Vec<String> = branches
+                .iter()
+                .enumerate()
+                .filter(|(_, errors)| errors.is_empty())
+                .map(|(index, _)| index.to_string())
+                .collect();
+
+            let reason = match matches.len() {
+                0 => Some(describe_branches(&branches)),
+                1 => None,
+                _ => Some(format!("schemas {} all match", matches.join(", "))),
+            };
+
+            if let Some(reason) = reason {
+                errors.push(Error::InvalidValue {
+                    expected: format!("a value matching exactly one of the schemas ({reason})")
+                        .into(),
+                    got: value.unexpected(),
+                });
+            }
+        }
+    }
+
+    fn resolve(&self, reference: &Raw) -> Result<&'a Raw, Error> {
+        let unresolvable = || {
+            Error::custom(format!(
+                "invalid schema: unresolvable reference {reference:?}"
+            ))
+        };
+
+        let Raw::String(reference) = reference else {
+            return Err(unresolvable());
+        };
+
+        let Some(pointer) = reference.strip_prefix('#') else {
+            return Err(unresolvable());
+        };
+
+        Pointer::parse(&percent_decode(pointer))?
+            .tokens()
+            .iter()
+            .try_fold(self.root, |target, token| match inner(target) {
+                Raw::Map(map) => map.get(&Raw::String(token.clone())),
+                Raw::Seq(sequence) => token
+                    .parse()
+                    .ok()
+                    .and_then(|index: usize| sequence.get(index)),
+                _ => None,
+            })
+            .ok_or_else(unresolvable)
+    }
+}
+
+fn is_type(name: &str, value: &Raw) -> bool {
+    match name {
+        "null" => matches!(value, Raw::Unit | Raw::Option(None)),
+        "boolean" => matches!(value, Raw::Bool(_)),
+        "integer" => number(value).is_some_and(Number::is_integer),
+        "number" => number(value).is_some(),
+        "string" => matches!(value, Raw::String(_) | Raw::Char(_)),
+        "array" => matches!(value, Raw::Seq(_)),
+        "object" => matches!(value, Raw::Map(_)),
+        _ => false,
+    }
+}
+
+/// Checks whether `n` is a multiple of the positive `factor`, exactly for integers
+/// and within a relative tolerance for floats (e.g. `0.3` is a multiple of `0.1`).
+fn is_multiple(n: Number, factor: Number) -> bool {
+    match (n, factor) {
+        (Number::Integer(n), Number::Integer(factor)) => n % factor == 0,
+        (n, factor) => {
+            let quotient = n.as_f64() / factor.as_f64();
+
+            (quotient - quotient.round()).abs() <= 16.0 * f64::EPSILON * quotient.abs().max(1.0)
+        }
+    }
+}
+
+fn check_length<'a>(
+    errors: &mut Vec<Error>,
+    length: usize,
+    unit: &str,
+    keyword: &impl Fn(&str) -> Option<&'a Raw>,
+    minimum: &str,
+    maximum: &str,
+) {
+    let bound = |name| keyword(name).and_then(number).map(|n| n.as_f64() as usize);
+
+    if let Some(minimum) = bound(minimum).filter(|minimum| length < *minimum) {
+        errors.push(Error::InvalidLength {
+            expected: format!("at least {minimum} {unit}").into(),
+            got: length,
+        });
+    }
+
+    if let Some(maximum) = bound(maximum).filter(|maximum| length > *maximum) {
+        errors.push(Error::InvalidLength {
+            expected: format!("at most {maximum} {unit}").into(),
+            got: length,
+        });
+    }
+}
+
+/// Describes why a value does not match any of the given `anyOf` or `oneOf` branches.
+fn describe_branches(branches: &[Vec<Error>]) -> String {
+    let branches: Vec<String> = branches
+        .iter()
+        .enumerate()
+        .map(|(index, errors)| {
+            let errors: Vec<String> = errors.iter().map(Error::to_string).collect();
+            format!("schema {index}: {}", errors.join(", "))
+        })
+        .collect();
+
+    branches.join("; ")
+}
+
+fn describe(raw: &Raw) -> String {
+    scalar(raw).map_or_else(
+        |_| "a specific value".to_owned(),
+        |value| format!("`{value}`"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Segment;
+    use crate::schema::{json_schema, object, optional, required, sequence, string, u8};
+
+    use serde_json::json;
+
+    fn value(json: serde_json::Value) -> Value {
+        serde_json::from_value(json).expect("Deserialize JSON")
+    }
+
+    #[test]
+    fn it_validates_values() {
+        let schema = value(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "role": { "enum": ["admin", "user"] },
+                "servers": { "type": "array", "items": { "$ref": "#/$defs/server" } },
+            },
+            "required": ["name", "servers"],
+            "additionalProperties": false,
+            "$defs": {
+                "server": {
+                    "type": "object",
+                    "properties": {
+                        "port": { "type": "integer", "minimum": 1, "maximum": 65535 },
+                        "host": { "oneOf": [{ "type": "string" }, { "type": "null" }] },
+                    },
+                    "required": ["port"],
+                },
+            },
+        }));
+
+        assert!(
+            validate(
+                &schema,
+                &value(json!({
+                    "name": "a",
+                    "role": "admin",
+                    "servers": [{ "port": 80, "host": null }],
+                }))
+            )
+            .is_ok()
+        );
+
+        let errors = validate(
+            &schema,
+            &value(json!({
+                "name": "",
+                "role": "guest",
+                "servers": [{ "port": 80 }, { "port": 0, "host": 1 }, {}],
+                "colour": "red",
+            })),
+        )
+        .expect_err("Value is invalid");
+
+        let summary: Vec<(&str, Vec<Segment>)> = errors
+            .iter()
+            .map(|error| (error.code(), error.path()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("unknown_field", vec![]),
+                ("invalid_length", vec![Segment::Key("name".to_owned())]),
+                ("invalid_value", vec![Segment::Key("role".to_owned())]),
+                (
+                    "invalid_value",
+                    vec![
+                        Segment::Key("servers".to_owned()),
+                        Segment::Index(1),
+                        Segment::Key("host".to_owned())
+                    ]
+                ),
+                (
+                    "out_of_range",
+                    vec![
+                        Segment::Key("servers".to_owned()),
+                        Segment::Index(1),
+                        Segment::Key("port".to_owned())
+                    ]
+                ),
+                (
+                    "missing_field",
+                    vec![Segment::Key("servers".to_owned()), Segment::Index(2)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_validates_against_generated_schemas() {
+        let codec = object((
+            required("name", string()),
+            optional("age", u8()),
+            required("tags", sequence(string())),
+        ));
+        let schema = json_schema(&codec);
+
+        assert!(validate(&schema, &value(json!({ "name": "a", "tags": [] }))).is_ok());
+        assert!(
+            validate(
+                &schema,
+                &value(json!({ "name": "a", "age": null, "tags": [] }))
+            )
+            .is_ok()
+        );
+
+        let errors = validate(
+            &schema,
+            &value(json!({ "name": "a", "age": 300, "tags": [1] })),
+        )
+        .expect_err("Value is invalid");
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].code(), "invalid_value");
+        assert_eq!(errors[1].code(), "invalid_type");
+    }
+
+    #[test]
+    fn it_rejects_cyclic_and_unresolvable_references() {
+        let cyclic = value(json!({ "$ref": "#" }));
+        assert!(validate(&cyclic, &value(json!(1))).is_err());
+
+        let unresolvable = value(json!({ "$ref": "#/$defs/missing" }));
+        assert!(validate(&unresolvable, &value(json!(1))).is_err());
+    }
+
+    #[test]
+    fn it_validates_patterns() {
+        let schema = value(json!({ "type": "string", "pattern": "^[a-z]+$" }));
+
+        assert!(validate(&schema, &value(json!("abc"))).is_ok());
+        assert_eq!(
+            validate(&schema, &value(json!("ABC"))).is_err(),
+            cfg!(feature = "regex")
+        );
+    }
+
+    #[test]
+    fn it_validates_multiples() {
+        let schema = |factor| value(json!({ "multipleOf": factor }));
+
+        assert!(validate(&schema(json!(0.1)), &value(json!(0.3))).is_ok());
+        assert!(validate(&schema(json!(0.1)), &value(json!(0.35))).is_err());
+        assert!(validate(&schema(json!(0.01)), &value(json!(19.99))).is_ok());
+        assert!(validate(&schema(json!(3)), &value(json!(9_007_199_254_740_993u64))).is_ok());
+        assert!(validate(&schema(json!(2)), &value(json!(9_007_199_254_740_993u64))).is_err());
+    }
+
+    #[test]
+    fn it_rejects_non_positive_multiples() {
+        let errors = validate(&value(json!({ "multipleOf": 0 })), &value(json!(4)))
+            .expect_err("Schema is invalid");
+
+        assert_eq!(errors[0].code(), "custom");
+        assert!(validate(&value(json!({ "multipleOf": -2 })), &value(json!(4))).is_err());
+    }
+
+    #[test]
+    fn it_resolves_percent_encoded_references() {
+        let schema = value(json!({
+            "$ref": "#/$defs/a%20b",
+            "$defs": { "a b": { "type": "string" } },
+        }));
+
+        assert!(validate(&schema, &value(json!("a"))).is_ok());
+        assert!(validate(&schema, &value(json!(1))).is_err());
+    }
+
+    #[test]
+    fn it_describes_failing_branches() {
+        let schema = value(json!({
+            "anyOf": [{ "type": "string" }, { "type": "integer", "minimum": 10 }],
+        }));
+
+        let errors = validate(&schema, &value(json!(1))).expect_err("Value is invalid");
+        let message = errors[0].to_string();
+
+        assert!(message.contains("schema 0: invalid type"), "{message}");
+        assert!(message.contains("schema 1: out of range"), "{message}");
+
+        let schema = value(json!({ "oneOf": [{ "type": "integer" }, { "minimum": 0 }] }));
+
+        let errors = validate(&schema, &value(json!(1))).expect_err("Value is invalid");
+        assert!(errors[0].to_string().contains("schemas 0, 1 all match"));
+    }
+
+    #[test]
+    fn it_validates_pattern_properties() {
+        let schema = value(json!({
+            "properties": { "name": { "type": "string" } },
+            "patternProperties": { "^x-": { "type": "integer" } },
+            "additionalProperties": false,
+        }));
+
+        assert!(validate(&schema, &value(json!({ "name": "a", "x-id": 1 }))).is_ok());
+        assert_eq!(
+            validate(&schema, &value(json!({ "x-id": "a" }))).is_err(),
+            cfg!(feature = "regex")
+        );
+        assert_eq!(
+            validate(&schema, &value(json!({ "colour": "red" }))).is_err(),
+            cfg!(feature = "regex")
+        );
+    }
+}
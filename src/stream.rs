@@ -0,0 +1,548 @@
+//! Read and decode values asynchronously from any [`AsyncRead`] implementor.
+//!
+//! This module is runtime-agnostic; it only relies on the [`futures-io`] traits.
+//!
+//! [`futures-io`]: https://docs.rs/futures-io
+use crate::lines::MAX_LINE_LENGTH;
+use crate::{Decoder, Error, Result, Value};
+
+use futures_io::{AsyncBufRead, AsyncRead};
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{Poll, ready};
+
+/// An asynchronous reader of newline-delimited values, like [JSON Lines].
+///
+/// Errors are annotated with the line that caused them. A line that is not valid
+/// UTF-8, is longer than the [maximum length](Self::max_length), or fails to
+/// deserialize does not stop the reader, so bad lines can be skipped; an I/O
+/// error does.
+///
+/// [JSON Lines]: https://jsonlines.org
+#[derive(Debug)]
+pub struct Lines<R, F> {
+    input: R,
+    deserialize: F,
+    buffer: Vec<u8>,
+    max_length: usize,
+    line: usize,
+    is_done: bool,
+}
+
+/// An asynchronous reader of length-delimited values.
+///
+/// Every frame starts with its length as a 32-bit big-endian unsigned integer,
+/// followed by the serialized value. Errors are annotated with the number of the
+/// frame that caused them. A frame that fails to deserialize does not stop the
+/// reader; an I/O error or a frame longer than the
+/// [maximum length](Self::max_length) does.
+#[derive(Debug)]
+pub struct Frames<R, F> {
+    input: R,
+    deserialize: F,
+    buffer: Vec<u8>,
+    max_length: usize,
+    frame: usize,
+    is_done: bool,
+}
+
+/// The default maximum length of a frame read by [`Frames`]: 16 MiB.
+pub const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Reads the whole input asynchronously, deserializes it with the given function,
+/// and runs a [`Decoder`] on the resulting [`Value`].
+///
+/// This is the asynchronous counterpart of [`run`](crate::run).
+pub async fn decode_async<T, R, F, E>(
+    mut input: R,
+    deserialize: F,
+    decoder: impl Decoder<Output = T>,
+) -> Result<T>
+where
+    R: AsyncRead + Unpin,
+    F: Fn(&[u8]) -> std::result::Result<Value, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut buffer = Vec::new();
+    let _ = read_to_end(&mut input, &mut buffer)
+        .await
+        .map_err(Error::deserializer)?;
+
+    decoder.run(deserialize(&buffer).map_err(Error::deserializer)?)
+}
+
+impl<R, F, E> Lines<R, F>
+where
+    R: AsyncBufRead + Unpin,
+    F: Fn(&str) -> std::result::Result<Value, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Creates a new [`Lines`] reader, using the given function to deserialize
+    /// each line into a [`Value`].
+    pub fn new(input: R, deserialize: F) -> Self {
+        Self {
+            input,
+            deserialize,
+            buffer: Vec::new(),
+            max_length: MAX_LINE_LENGTH,
+            line: 0,
+            is_done: false,
+        }
+    }
+
+    /// Sets the maximum length of a line, in bytes, excluding the newline.
+    ///
+    /// Longer lines are skipped without being buffered, failing with an
+    /// [`Error::InvalidLength`]. By default, it is [`MAX_LINE_LENGTH`].
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Returns the number of the last line read.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Reads the next [`Value`], if any.
+    pub async fn next(&mut self) -> Option<Result<Value>> {
+        while !self.is_done {
+            self.buffer.clear();
+            self.line += 1;
+
+            let limit = self.max_length.saturating_add(1);
+
+            let line = match read_line(&mut self.input, &mut self.buffer, limit).await {
+                Ok(0) => {
+                    self.is_done = true;
+                    continue;
+                }
+                Ok(_) if self.buffer.len() > self.max_length && !self.buffer.ends_with(b"\n") => {
+                    match skip_line(&mut self.input).await {
+                        Ok(skipped) => Err(Error::InvalidLength {
+                            expected: format!("a line of at most {} bytes", self.max_length).into(),
+                            got: self.buffer.len() + skipped,
+                        }),
+                        Err(error) => {
+                            self.is_done = true;
+                            Err(Error::deserializer(error))
+                        }
+                    }
+                }
+                Ok(_) => std::str::from_utf8(&self.buffer).map_err(Error::deserializer),
+                Err(error) => {
+                    self.is_done = true;
+                    Err(Error::deserializer(error))
+                }
+            };
+
+            match line {
+                Ok(line) if line.trim().is_empty() => {}
+                Ok(line) => {
+                    return Some(
+                        (self.deserialize)(line)
+                            .map_err(|error| origin(Error::deserializer(error), self.line)),
+                    );
+                }
+                Err(error) => return Some(Err(origin(error, self.line))),
+            }
+        }
+
+        None
+    }
+
+    /// Reads the next [`Value`], if any, and decodes it with the given [`Decoder`].
+    pub async fn decode<T>(&mut self, decoder: &impl Decoder<Output = T>) -> Option<Result<T>> {
+        let value = self.next().await?;
+
+        Some(value.and_then(|value| decoder.run(value).map_err(|error| origin(error, self.line))))
+    }
+}
+
+impl<R, F, E> Frames<R, F>
+where
+    R: AsyncRead + Unpin,
+    F: Fn(&[u8]) -> std::result::Result<Value, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Creates a new [`Frames`] reader, using the given function to deserialize
+    /// each frame into a [`Value`].
+    pub fn new(input: R, deserialize: F) -> Self {
+        Self {
+            input,
+            deserialize,
+            buffer: Vec::new(),
+            max_length: MAX_FRAME_LENGTH,
+            frame: 0,
+            is_done: false,
+        }
+    }
+
+    /// Sets the maximum length of a frame, in bytes.
+    ///
+    /// Lengths are checked before reading a frame, so a corrupt or malicious
+    /// length cannot cause a large allocation. By default, it is
+    /// [`MAX_FRAME_LENGTH`].
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Returns the number of the last frame read.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Reads the next [`Value`], if any.
+    pub async fn next(&mut self) -> Option<Result<Value>> {
+        if self.is_done {
+            return None;
+        }
+
+        let mut length = [0; 4];
+
+        match read_exact(&mut self.input, &mut length).await {
+            Ok(true) => {}
+            Ok(false) => {
+                self.is_done = true;
+                return None;
+            }
+            Err(error) => {
+                self.is_done = true;
+                return Some(Err(origin_frame(
+                    Error::deserializer(error),
+                    self.frame + 1,
+                )));
+            }
+        }
+
+        self.frame += 1;
+
+        let length = u32::from_be_bytes(length) as usize;
+
+        if length > self.max_length {
+            self.is_done = true;
+
+            return Some(Err(origin_frame(
+                Error::InvalidLength {
+                    expected: format!("a frame of at most {} bytes", self.max_length).into(),
+                    got: length,
+                },
+                self.frame,
+            )));
+        }
+
+        self.buffer.resize(length, 0);
+
+        let frame = match read_exact(&mut self.input, &mut self.buffer).await {
+            Ok(true) => (self.deserialize)(&self.buffer).map_err(Error::deserializer),
+            Ok(false) => {
+                self.is_done = true;
+
+                Err(Error::deserializer(io::Error::from(
+                    io::ErrorKind::UnexpectedEof,
+                )))
+            }
+            Err(error) => {
+                self.is_done = true;
+
+                Err(Error::deserializer(error))
+            }
+        };
+
+        Some(frame.map_err(|error| origin_frame(error, self.frame)))
+    }
+
+    /// Reads the next [`Value`], if any, and decodes it with the given [`Decoder`].
+    pub async fn decode<T>(&mut self, decoder: &impl Decoder<Output = T>) -> Option<Result<T>> {
+        let value = self.next().await?;
+
+        Some(value.and_then(|value| {
+            decoder
+                .run(value)
+                .map_err(|error| origin_frame(error, self.frame))
+        }))
+    }
+}
+
+fn origin(error: Error, line: usize) -> Error {
+    error.origin(format!("line {line}"))
+}
+
+fn origin_frame(error: Error, frame: usize) -> Error {
+    error.origin(format!("frame {frame}"))
+}
+
+/// Reads a line, including its newline, reading at most `limit` bytes.
+async fn read_line<R>(input: &mut R, buffer: &mut Vec<u8>, limit: usize) -> io::Result<usize>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut read = 0;
+
+    poll_fn(|cx| {
+        loop {
+            let available = ready!(Pin::new(&mut *input).poll_fill_buf(cx))?;
+            let is_empty = available.is_empty();
+            let available = &available[..available.len().min(limit - read)];
+
+            let (is_done, used) = match available.iter().position(|byte| *byte == b'\n') {
+                Some(i) => (true, i + 1),
+                None => (is_empty || read + available.len() == limit, available.len()),
+            };
+
+            buffer.extend_from_slice(&available[..used]);
+            Pin::new(&mut *input).consume(used);
+            read += used;
+
+            if is_done {
+                return Poll::Ready(Ok(read));
+            }
+        }
+    })
+    .await
+}
+
+/// Consumes the rest of a line without buffering it, returning its length.
+async fn skip_line<R>(input: &mut R) -> io::Result<usize>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut skipped = 0;
+
+    poll_fn(|cx| {
+        loop {
+            let available = ready!(Pin::new(&mut *input).poll_fill_buf(cx))?;
+
+            match available.iter().position(|byte| *byte == b'\n') {
+                Some(end) => {
+                    Pin::new(&mut *input).consume(end + 1);
+                    return Poll::Ready(Ok(skipped + end));
+                }
+                None if available.is_empty() => return Poll::Ready(Ok(skipped)),
+                None => {
+                    let length = available.len();
+                    Pin::new(&mut *input).consume(length);
+                    skipped += length;
+                }
+            }
+        }
+    })
+    .await
+}
+
+/// Fills the whole buffer, returning `false` if the input ends before reading anything.
+async fn read_exact<R>(input: &mut R, buffer: &mut [u8]) -> io::Result<bool>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut filled = 0;
+
+    poll_fn(|cx| {
+        while filled < buffer.len() {
+            match ready!(Pin::new(&mut *input).poll_read(cx, &mut buffer[filled..]))? {
+                0 if filled == 0 => return Poll::Ready(Ok(false)),
+                0 => return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into())),
+                read => filled += read,
+            }
+        }
+
+        Poll::Ready(Ok(true))
+    })
+    .await
+}
+
+async fn read_to_end<R>(input: &mut R, buffer: &mut Vec<u8>) -> io::Result<usize>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut chunk = [0; 8 * 1024];
+    let mut read = 0;
+
+    poll_fn(|cx| {
+        loop {
+            match ready!(Pin::new(&mut *input).poll_read(cx, &mut chunk))? {
+                0 => return Poll::Ready(Ok(read)),
+                n => {
+                    buffer.extend_from_slice(&chunk[..n]);
+                    read += n;
+                }
+            }
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{map, string, u32};
+
+    use std::task::{Context, Waker};
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Packet {
+        sequence: u32,
+        payload: String,
+    }
+
+    impl Packet {
+        fn decode(value: Value) -> Result<Self> {
+            let mut packet = map(value)?;
+
+            Ok(Self {
+                sequence: packet.required("sequence", u32)?,
+                payload: packet.required("payload", string)?,
+            })
+        }
+    }
+
+    /// Polls a future until completion; in-memory readers never return `Pending`.
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        let mut future = std::pin::pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn it_decodes_async_input() {
+        let input: &[u8] = br#"{ "sequence": 1, "payload": "a" }"#;
+
+        let packet = block_on(decode_async(
+            input,
+            |bytes: &[u8]| serde_json::from_slice(bytes),
+            Packet::decode,
+        ))
+        .expect("Decode packet");
+
+        assert_eq!(packet.payload, "a");
+    }
+
+    #[test]
+    fn it_decodes_async_lines() {
+        let input: &[u8] =
+            b"{ \"sequence\": 1, \"payload\": \"a\" }\n{ \"sequence\": 2 }\n\n{ \"sequence\": 3, \"payload\": \"c\" }";
+        let mut lines = Lines::new(input, |line: &str| serde_json::from_str(line));
+
+        let packets = block_on(async {
+            let mut packets = Vec::new();
+
+            while let Some(packet) = lines.decode(&Packet::decode).await {
+                packets.push(packet);
+            }
+
+            packets
+        });
+
+        assert_eq!(packets.len(), 3);
+        assert_eq!(packets[0].as_ref().unwrap().sequence, 1);
+        assert!(matches!(&packets[1], Err(Error::Origin { origin, .. }) if origin == "line 2"));
+        assert_eq!(packets[2].as_ref().unwrap().sequence, 3);
+    }
+
+    #[test]
+    fn it_skips_async_lines_that_are_too_long() {
+        let input = format!(
+            "{}\n{{ \"sequence\": 2, \"payload\": \"b\" }}\n",
+            "x".repeat(1000)
+        );
+        let mut lines =
+            Lines::new(input.as_bytes(), |line: &str| serde_json::from_str(line)).max_length(64);
+
+        let packets = block_on(async {
+            let mut packets = Vec::new();
+
+            while let Some(packet) = lines.decode(&Packet::decode).await {
+                packets.push(packet);
+            }
+
+            packets
+        });
+
+        assert_eq!(packets.len(), 2);
+        assert!(matches!(
+            &packets[0],
+            Err(Error::Origin { origin, error })
+                if origin == "line 1" && matches!(**error, Error::InvalidLength { got: 1000, .. })
+        ));
+        assert_eq!(packets[1].as_ref().unwrap().sequence, 2);
+    }
+
+    #[test]
+    fn it_decodes_async_frames() {
+        let mut input = Vec::new();
+
+        for frame in [
+            r#"{ "sequence": 1, "payload": "a" }"#,
+            r#"{ "sequence": 2, "payload": "b" }"#,
+        ] {
+            input.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+            input.extend_from_slice(frame.as_bytes());
+        }
+
+        input.extend_from_slice(&[0, 0, 0, 10, b'{']);
+
+        let mut frames = Frames::new(input.as_slice(), |bytes: &[u8]| {
+            serde_json::from_slice(bytes)
+        });
+
+        let packets = block_on(async {
+            let mut packets = Vec::new();
+
+            while let Some(packet) = frames.decode(&Packet::decode).await {
+                packets.push(packet);
+            }
+
+            packets
+        });
+
+        assert_eq!(packets.len(), 3);
+        assert_eq!(packets[1].as_ref().unwrap().payload, "b");
+        assert!(matches!(&packets[2], Err(Error::Origin { origin, .. }) if origin == "frame 3"));
+    }
+
+    #[test]
+    fn it_skips_invalid_frames_and_rejects_long_ones() {
+        let mut input = Vec::new();
+
+        for frame in [
+            "{",
+            r#"{ "sequence": 2, "payload": "b" }"#,
+            "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+        ] {
+            input.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+            input.extend_from_slice(frame.as_bytes());
+        }
+
+        input.extend_from_slice(&u32::MAX.to_be_bytes());
+
+        let mut frames = Frames::new(input.as_slice(), |bytes: &[u8]| {
+            serde_json::from_slice(bytes)
+        })
+        .max_length(40);
+
+        let packets = block_on(async {
+            let mut packets = Vec::new();
+
+            while let Some(packet) = frames.decode(&Packet::decode).await {
+                packets.push(packet);
+            }
+
+            packets
+        });
+
+        assert_eq!(packets.len(), 3);
+        assert!(packets[0].is_err());
+        assert_eq!(packets[1].as_ref().unwrap().sequence, 2);
+        assert!(matches!(
+            &packets[2],
+            Err(Error::Origin { error, .. }) if matches!(**error, Error::InvalidLength { got: 41, .. })
+        ));
+    }
+}
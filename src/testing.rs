@@ -0,0 +1,208 @@
+//! Test your decoders and encoders with generated values.
+//!
+//! [`round_trip`] checks that decoding an encoded value produces the original
+//! one, and [`fuzz`] checks that a [`Decoder`] never panics on arbitrary
+//! [`Value`]s. Both are built on [`proptest`], so failing inputs are shrunk to a
+//! minimal case and the amount of cases can be set with the `PROPTEST_CASES`
+//! environment variable:
+//!
+//! ```rust
+//! use decoder::testing::{fuzz, round_trip};
+//! use decoder::{Result, Value};
+//! use proptest::prelude::*;
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! impl Point {
+//!     fn decode(value: Value) -> Result<Self> {
+//!         use decoder::decode::{i32, map};
+//!
+//!         let mut point = map(value)?;
+//!
+//!         Ok(Self {
+//!             x: point.required("x", i32)?,
+//!             y: point.required("y", i32)?,
+//!         })
+//!     }
+//!
+//!     fn encode(&self) -> Value {
+//!         use decoder::encode::{i32, map};
+//!
+//!         map([("x", i32(self.x)), ("y", i32(self.y))]).into()
+//!     }
+//! }
+//!
+//! round_trip(
+//!     (any::<i32>(), any::<i32>()).prop_map(|(x, y)| Point { x, y }),
+//!     Point::encode,
+//!     Point::decode,
+//! );
+//!
+//! fuzz(Point::decode);
+//! ```
+//!
+//! [`assert_snapshot`] compares the encoding of a value against a golden file,
+//! which is useful to catch accidental changes in encoders.
+//!
+//! [`proptest`]: https://docs.rs/proptest
+use crate::value::Raw;
+use crate::{Decoder, Value};
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestCaseError, TestRunner};
+
+use std::fmt::Debug;
+
+mod snapshot;
+
+pub use snapshot::{Order, Snapshot, UPDATE, assert_snapshot, render};
+
+/// Checks that decoding the encoding of every value generated by the given
+/// strategy produces the original value.
+///
+/// # Panics
+/// Panics with the minimal failing value if the round trip fails.
+pub fn round_trip<T, E>(
+    strategy: impl Strategy<Value = T>,
+    encode: impl Fn(&T) -> E,
+    decoder: impl Decoder<Output = T>,
+) where
+    T: Debug + PartialEq,
+    E: Into<Value>,
+{
+    let result = runner().run(&strategy, |input| {
+        let encoded = encode(&input).into();
+
+        let decoded = decoder.run(encoded.clone()).map_err(|error| {
+            TestCaseError::fail(format!("decoding failed: {error} (encoded: {encoded:?})"))
+        })?;
+
+        prop_assert_eq!(decoded, input, "encoded: {:?}", encoded);
+
+        Ok(())
+    });
+
+    if let Err(error) = result {
+        panic!("round trip failed: {error}");
+    }
+}
+
+/// Runs the given [`Decoder`] with arbitrary [`Value`]s, checking that it never
+/// panics.
+///
+/// # Panics
+/// Panics with the minimal [`Value`] that makes the [`Decoder`] panic.
+pub fn fuzz<T>(decoder: impl Decoder<Output = T>) {
+    let result = runner().run(&value(), |value| {
+        let _ = decoder.run(value);
+
+        Ok(())
+    });
+
+    if let Err(error) = result {
+        panic!("decoder panicked: {error}");
+    }
+}
+
+/// A strategy that generates arbitrary [`Value`]s, using every variant of the
+/// underlying data model.
+pub fn value() -> impl Strategy<Value = Value> {
+    raw().prop_map(Value)
+}
+
+/// Unlike the `Arbitrary` implementation of `decoder_value` used by its fuzz
+/// targets, which consumes raw bytes, this strategy shrinks failing trees
+/// structurally.
+pub(crate) fn raw() -> impl Strategy<Value = Raw> {
+    let leaf = prop_oneof![
+        any::<bool>().prop_map(Raw::Bool),
+        any::<u8>().prop_map(Raw::U8),
+        any::<u16>().prop_map(Raw::U16),
+        any::<u32>().prop_map(Raw::U32),
+        any::<u64>().prop_map(Raw::U64),
+        any::<i8>().prop_map(Raw::I8),
+        any::<i16>().prop_map(Raw::I16),
+        any::<i32>().prop_map(Raw::I32),
+        any::<i64>().prop_map(Raw::I64),
+        any::<f32>().prop_map(Raw::F32),
+        any::<f64>().prop_map(Raw::F64),
+        any::<char>().prop_map(Raw::Char),
+        vec(any::<char>(), 0..16).prop_map(|chars| Raw::String(chars.into_iter().collect())),
+        Just(Raw::Unit),
+        Just(Raw::Option(None)),
+        vec(any::<u8>(), 0..16).prop_map(Raw::Bytes),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            inner
+                .clone()
+                .prop_map(|raw| Raw::Option(Some(Box::new(raw)))),
+            inner.clone().prop_map(|raw| Raw::Newtype(Box::new(raw))),
+            vec(inner.clone(), 0..8).prop_map(Raw::Seq),
+            vec((inner.clone(), inner), 0..8)
+                .prop_map(|fields| Raw::Map(fields.into_iter().collect())),
+        ]
+    })
+}
+
+fn runner() -> TestRunner {
+    TestRunner::new(Config {
+        failure_persistence: None,
+        ..Config::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode, encode};
+
+    use std::time::Duration;
+
+    #[test]
+    fn it_round_trips() {
+        round_trip(
+            any::<Vec<Option<u16>>>(),
+            |items| encode::sequence(|item| encode::optional(encode::u16, *item), items),
+            decode::sequence(decode::optional(decode::u16)),
+        );
+
+        round_trip(
+            (any::<u64>(), 0..1_000_000_000u32)
+                .prop_map(|(secs, nanos)| Duration::new(secs, nanos)),
+            |duration| encode::duration_human(*duration),
+            decode::duration_human,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "round trip failed")]
+    fn it_detects_lossy_round_trips() {
+        round_trip(any::<i64>(), |n| encode::i32(*n as i32), decode::i64);
+    }
+
+    #[test]
+    fn it_fuzzes_decoders() {
+        fuzz(decode::u8);
+        fuzz(decode::string);
+        fuzz(decode::char);
+        fuzz(decode::duration);
+        fuzz(decode::duration_human);
+        fuzz(decode::byte_size);
+        fuzz(decode::system_time);
+        fuzz(decode::lenient(decode::f64));
+        fuzz(decode::sequence::<_, Vec<_>>(decode::optional(
+            decode::bool,
+        )));
+        fuzz(decode::strict(|fields| {
+            fields.required("a", decode::string)
+        }));
+        fuzz(crate::patch::Patch::decode);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::Value;
+use crate::value::Raw;
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable that enables the update mode of a [`Snapshot`].
+pub const UPDATE: &str = "DECODER_UPDATE_SNAPSHOTS";
+
+/// The order of the keys of maps in a rendered [`Value`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    /// Keys keep their order of insertion.
+    #[default]
+    Insertion,
+    /// Keys are sorted.
+    Sorted,
+}
+
+/// A golden file containing the canonical rendering of an encoded [`Value`].
+///
+/// Paths are relative to the working directory, which is the package root when
+/// running `cargo test`.
+///
+/// If the `DECODER_UPDATE_SNAPSHOTS` environment variable is set to `1`, the
+/// golden file is written instead of compared.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    path: PathBuf,
+    order: Order,
+}
+
+impl Snapshot {
+    /// Creates a new [`Snapshot`] stored in the given path.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            order: Order::default(),
+        }
+    }
+
+    /// Sets the [`Order`] of the keys of maps.
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Compares the rendering of the given [`Value`] with the golden file.
+    ///
+    /// # Panics
+    /// Panics with a diff if the rendering does not match the golden file, or if
+    /// the golden file does not exist.
+    pub fn assert(&self, value: impl Into<Value>) {
+        let update = std::env::var(UPDATE).is_ok_and(|update| update == "1");
+
+        if let Err(message) = self.check(&render(&value.into(), self.order), update) {
+            panic!("{message}");
+        }
+    }
+
+    fn check(&self, rendered: &str, update: bool) -> Result<(), String> {
+        let path = self.path.display();
+
+        if update {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|error| format!("could not create {}: {error}", parent.display()))?;
+            }
+
+            return fs::write(&self.path, rendered)
+                .map_err(|error| format!("could not write snapshot {path}: {error}"));
+        }
+
+        let expected = fs::read_to_string(&self.path).map_err(|error| {
+            format!("could not read snapshot {path}: {error} (run with {UPDATE}=1 to create it)")
+        })?;
+
+        if expected == rendered {
+            Ok(())
+        } else {
+            Err(format!(
+                "snapshot {path} does not match (run with {UPDATE}=1 to update it):\n{}",
+                diff(&expected, rendered)
+            ))
+        }
+    }
+}
+
+/// Compares the rendering of the given [`Value`] with the golden file in the given
+/// path, keeping the insertion order of keys.
+///
+/// See [`Snapshot`].
+///
+/// # Panics
+/// Panics with a diff if the rendering does not match the golden file.
+pub fn assert_snapshot(path: impl AsRef<Path>, value: impl Into<Value>) {
+    Snapshot::new(path).assert(value);
+}
+
+/// Renders a [`Value`] in a canonical, JSON-like format.
+///
+/// Maps and sequences are indented with two spaces and options and newtypes are
+/// transparent. Floats always contain a decimal point, and bytes are rendered as
+/// sequences of numbers.
+pub fn render(value: &Value, order: Order) -> String {
+    let mut output = String::new();
+
+    render_raw(&mut output, &value.0, order, 0);
+    output.push('\n');
+
+    output
+}
+
+fn render_raw(output: &mut String, raw: &Raw, order: Order, depth: usize) {
+    let _ = match raw {
+        Raw::Bool(b) => write!(output, "{b}"),
+        Raw::U8(n) => write!(output, "{n}"),
+        Raw::U16(n) => write!(output, "{n}"),
+        Raw::U32(n) => write!(output, "{n}"),
+        Raw::U64(n) => write!(output, "{n}"),
+        Raw::I8(n) => write!(output, "{n}"),
+        Raw::I16(n) => write!(output, "{n}"),
+        Raw::I32(n) => write!(output, "{n}"),
+        Raw::I64(n) => write!(output, "{n}"),
+        Raw::F32(n) => write!(output, "{n:?}"),
+        Raw::F64(n) => write!(output, "{n:?}"),
+        Raw::Char(c) => write!(output, "{:?}", c.to_string()),
+        Raw::String(string) => write!(output, "{string:?}"),
+        Raw::Unit | Raw::Option(None) => write!(output, "null"),
+        Raw::Option(Some(raw)) | Raw::Newtype(raw) => {
+            render_raw(output, raw, order, depth);
+            Ok(())
+        }
+        Raw::Bytes(bytes) => {
+            let items: Vec<Raw> = bytes.iter().copied().map(Raw::U8).collect();
+
+            render_seq(output, &items, order, depth);
+            Ok(())
+        }
+        Raw::Seq(items) => {
+            render_seq(output, items, order, depth);
+            Ok(())
+        }
+        Raw::Map(map) => {
+            let mut fields: Vec<(String, &Raw)> = map
+                .iter()
+                .map(|(key, value)| (render_key(key, order), value))
+                .collect();
+
+            if order == Order::Sorted {
+                fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            }
+
+            if fields.is_empty() {
+                output.push_str("{}");
+                return;
+            }
+
+            output.push_str("{\n");
+
+            for (i, (key, value)) in fields.iter().enumerate() {
+                indent(output, depth + 1);
+                output.push_str(key);
+                output.push_str(": ");
+                render_raw(output, value, order, depth + 1);
+
+                if i + 1 < fields.len() {
+                    output.push(',');
+                }
+
+                output.push('\n');
+            }
+
+            indent(output, depth);
+            output.push('}');
+            Ok(())
+        }
+    };
+}
+
+fn render_seq(output: &mut String, items: &[Raw], order: Order, depth: usize) {
+    if items.is_empty() {
+        output.push_str("[]");
+        return;
+    }
+
+    output.push_str("[\n");
+
+    for (i, item) in items.iter().enumerate() {
+        indent(output, depth + 1);
+        render_raw(output, item, order, depth + 1);
+
+        if i + 1 < items.len() {
+            output.push(',');
+        }
+
+        output.push('\n');
+    }
+
+    indent(output, depth);
+    output.push(']');
+}
+
+fn render_key(key: &Raw, order: Order) -> String {
+    match crate::value::inner(key) {
+        Raw::String(key) => format!("{key:?}"),
+        key => {
+            let mut rendered = String::new();
+            render_raw(&mut rendered, key, order, 0);
+
+            format!("{rendered:?}")
+        }
+    }
+}
+
+fn indent(output: &mut String, depth: usize) {
+    output.extend(std::iter::repeat_n("  ", depth));
+}
+
+/// Computes a line diff between two strings, based on their longest common
+/// subsequence of lines.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let mut lengths = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            let _ = writeln!(output, "  {}", expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len()
+            && (j == actual.len() || lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            let _ = writeln!(output, "- {}", expected[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(output, "+ {}", actual[j]);
+            j += 1;
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode;
+
+    fn server(port: u16) -> Value {
+        encode::map([
+            ("name", encode::string("api")),
+            ("port", encode::u16(port)),
+            ("ratio", encode::f64(1.0)),
+            ("tags", encode::sequence(encode::string, ["a", "b"])),
+            ("extra", encode::optional(encode::string, None::<&str>)),
+        ])
+        .into()
+    }
+
+    #[test]
+    fn it_renders_values() {
+        assert_eq!(
+            render(&server(80), Order::Insertion),
+            "{\n  \"name\": \"api\",\n  \"port\": 80,\n  \"ratio\": 1.0,\n  \"tags\": [\n    \"a\",\n    \"b\"\n  ],\n  \"extra\": null\n}\n"
+        );
+
+        assert!(
+            render(&server(80), Order::Sorted).starts_with("{\n  \"extra\": null,\n  \"name\"")
+        );
+    }
+
+    #[test]
+    fn it_compares_snapshots() {
+        let path = std::env::temp_dir()
+            .join(format!("decoder-snapshot-{}", std::process::id()))
+            .join("server.snap");
+
+        let snapshot = Snapshot::new(&path);
+        let rendered = render(&server(80), Order::Insertion);
+
+        assert!(snapshot.check(&rendered, false).is_err());
+        assert!(snapshot.check(&rendered, true).is_ok());
+        assert!(snapshot.check(&rendered, false).is_ok());
+
+        snapshot.assert(server(80));
+
+        let message = snapshot
+            .check(&render(&server(8080), Order::Insertion), false)
+            .expect_err("Snapshot does not match");
+
+        assert!(message.contains("-   \"port\": 80,\n+   \"port\": 8080,\n"));
+
+        let _ = fs::remove_dir_all(path.parent().expect("Snapshot has a parent"));
+    }
+}
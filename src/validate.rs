@@ -0,0 +1,410 @@
+//! Validate decoded values.
+//!
+//! Validations are available as methods of any [`Decoder`] and fail with an
+//! [`Error::InvalidValue`], or an [`Error::InvalidLength`] for lengths:
+//!
+//! ```rust
+//! use decoder::Decoder;
+//! use decoder::decode::{map, sequence, string, u16};
+//!
+//! let decoder = |value| {
+//!     let mut server = map(value)?;
+//!
+//!     Ok((
+//!         server.required("host", string.min_len(1).max_len(64))?,
+//!         server.required("port", u16.range(1..))?,
+//!         server.required("tags", sequence::<String, Vec<_>>(string).unique())?,
+//!     ))
+//! };
+//!
+//! let error = decoder::run(
+//!     serde_json::from_str,
+//!     decoder,
+//!     r#"{ "host": "localhost", "port": 0, "tags": [] }"#,
+//! )
+//! .expect_err("Port is invalid");
+//!
+//! assert_eq!(error.to_string(), "field `port`: invalid value (expected: at least 1, got: Unsigned(0))");
+//! ```
+use crate::{Decoder, Error, Map, Unexpected, Value};
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Bound, RangeBounds};
+
+/// The maximum amount of characters of a string described in an [`Error`].
+const MAX_CHARS: usize = 64;
+
+/// A value with a length.
+///
+/// See [`Decoder::min_len`] and [`Decoder::max_len`].
+pub trait Length {
+    /// Returns the length of the value.
+    ///
+    /// The length of a string is its amount of characters.
+    fn length(&self) -> usize;
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for VecDeque<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for HashMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for BTreeMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for HashSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for BTreeSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Length for Map {
+    fn length(&self) -> usize {
+        self.raw.len()
+    }
+}
+
+/// A value that can be described in an [`Error`].
+///
+/// See [`Decoder::range`] and [`Decoder::ensure`].
+pub trait Describe {
+    /// Returns the [`Unexpected`] description of the value.
+    ///
+    /// Strings are truncated, and collections are only described by their kind.
+    fn describe(&self) -> Unexpected;
+}
+
+macro_rules! describe {
+    ($variant:ident($as:ty): $($type:ty),*) => {
+        $(
+            impl Describe for $type {
+                fn describe(&self) -> Unexpected {
+                    Unexpected::$variant(<$as>::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+describe!(Unsigned(u64): u8, u16, u32, u64);
+describe!(Signed(i64): i8, i16, i32, i64);
+describe!(Float(f64): f32, f64);
+describe!(Bool(bool): bool);
+describe!(Char(char): char);
+
+impl Describe for usize {
+    fn describe(&self) -> Unexpected {
+        Unexpected::Unsigned(*self as u64)
+    }
+}
+
+impl Describe for isize {
+    fn describe(&self) -> Unexpected {
+        Unexpected::Signed(*self as i64)
+    }
+}
+
+impl Describe for str {
+    fn describe(&self) -> Unexpected {
+        match self.char_indices().nth(MAX_CHARS) {
+            Some((end, _)) => Unexpected::Str(format!("{}…", &self[..end])),
+            None => Unexpected::Str(self.to_owned()),
+        }
+    }
+}
+
+impl Describe for String {
+    fn describe(&self) -> Unexpected {
+        self.as_str().describe()
+    }
+}
+
+impl<T: Describe + ?Sized> Describe for &T {
+    fn describe(&self) -> Unexpected {
+        (**self).describe()
+    }
+}
+
+impl<T: Describe> Describe for Option<T> {
+    fn describe(&self) -> Unexpected {
+        self.as_ref().map_or(Unexpected::Option, Describe::describe)
+    }
+}
+
+macro_rules! describe_as {
+    ($variant:ident: $($type:ty),*) => {
+        $(
+            impl<T> Describe for $type {
+                fn describe(&self) -> Unexpected {
+                    Unexpected::$variant
+                }
+            }
+        )*
+    };
+}
+
+describe_as!(Seq: [T], Vec<T>, VecDeque<T>, HashSet<T>, BTreeSet<T>);
+
+impl<K, V> Describe for HashMap<K, V> {
+    fn describe(&self) -> Unexpected {
+        Unexpected::Map
+    }
+}
+
+impl<K, V> Describe for BTreeMap<K, V> {
+    fn describe(&self) -> Unexpected {
+        Unexpected::Map
+    }
+}
+
+impl Describe for Map {
+    fn describe(&self) -> Unexpected {
+        Unexpected::Map
+    }
+}
+
+impl Describe for Value {
+    fn describe(&self) -> Unexpected {
+        match &self.0 {
+            crate::value::Raw::String(string) => string.describe(),
+            raw => raw.unexpected(),
+        }
+    }
+}
+
+pub(crate) fn ensure<D>(
+    decoder: D,
+    expected: Cow<'static, str>,
+    predicate: impl Fn(&D::Output) -> bool,
+) -> impl Decoder<Output = D::Output>
+where
+    D: Decoder,
+    D::Output: Describe,
+{
+    move |value: Value| {
+        let output = decoder.run(value)?;
+
+        if predicate(&output) {
+            Ok(output)
+        } else {
+            Err(Error::InvalidValue {
+                expected: expected.clone(),
+                got: output.describe(),
+            })
+        }
+    }
+}
+
+pub(crate) fn ensure_length<D>(
+    decoder: D,
+    expected: Cow<'static, str>,
+    predicate: impl Fn(usize) -> bool,
+) -> impl Decoder<Output = D::Output>
+where
+    D: Decoder,
+    D::Output: Length,
+{
+    move |value: Value| {
+        let output = decoder.run(value)?;
+        let length = output.length();
+
+        if predicate(length) {
+            Ok(output)
+        } else {
+            Err(Error::InvalidLength {
+                expected: expected.clone(),
+                got: length,
+            })
+        }
+    }
+}
+
+pub(crate) fn unique<D, T>(decoder: D) -> impl Decoder<Output = D::Output>
+where
+    D: Decoder,
+    D::Output: AsRef<[T]>,
+    T: Hash + Eq + Describe,
+{
+    move |value: Value| {
+        let output = decoder.run(value)?;
+        let mut seen = HashSet::new();
+
+        for (index, item) in output.as_ref().iter().enumerate() {
+            if !seen.insert(item) {
+                return Err(Error::InvalidValue {
+                    expected: "a unique item".into(),
+                    got: item.describe(),
+                }
+                .item(index));
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(feature = "regex")]
+pub(crate) fn matches<D>(decoder: D, regex: regex::Regex) -> impl Decoder<Output = D::Output>
+where
+    D: Decoder,
+    D::Output: AsRef<str>,
+{
+    move |value: Value| {
+        let output = decoder.run(value)?;
+
+        if regex.is_match(output.as_ref()) {
+            Ok(output)
+        } else {
+            Err(Error::InvalidValue {
+                expected: format!("a string matching `{regex}`").into(),
+                got: output.as_ref().describe(),
+            })
+        }
+    }
+}
+
+pub(crate) fn describe_range<T: Debug>(range: &impl RangeBounds<T>) -> String {
+    let start = match range.start_bound() {
+        Bound::Included(start) => Some(format!("at least {start:?}")),
+        Bound::Excluded(start) => Some(format!("greater than {start:?}")),
+        Bound::Unbounded => None,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(end) => Some(format!("at most {end:?}")),
+        Bound::Excluded(end) => Some(format!("less than {end:?}")),
+        Bound::Unbounded => None,
+    };
+
+    match (start, end) {
+        (Some(start), Some(end)) => format!("{start} and {end}"),
+        (Some(bound), None) | (None, Some(bound)) => bound,
+        (None, None) => "any value".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode::{map, sequence, string, u16};
+    use crate::{Decoder, Error, Segment, Unexpected, Value, encode};
+
+    #[test]
+    fn it_validates_decoded_values() {
+        let port = u16.range(1..=1024);
+        assert_eq!(port.run(encode::u16(80)).ok(), Some(80));
+        assert!(matches!(
+            port.run(encode::u16(8080)),
+            Err(Error::InvalidValue { expected, .. }) if expected == "at least 1 and at most 1024"
+        ));
+
+        let name = string.min_len(2).max_len(4);
+        assert!(name.run(encode::string("añb")).is_ok());
+        assert!(name.run(encode::string("a")).is_err());
+        assert!(matches!(
+            name.run(encode::string("abcde")),
+            Err(Error::InvalidLength { expected, got: 5 }) if expected == "at most 4"
+        ));
+
+        let even = u16.ensure("an even number", |n| n % 2 == 0);
+        assert!(even.run(encode::u16(2)).is_ok());
+        assert!(matches!(
+            even.run(encode::u16(3)),
+            Err(Error::InvalidValue {
+                got: Unexpected::Unsigned(3),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn it_truncates_described_strings() {
+        let error = string
+            .ensure("a short string", |string| string.len() < 10)
+            .run(encode::string("a".repeat(1000)))
+            .expect_err("String is long");
+
+        assert!(matches!(
+            error,
+            Error::InvalidValue { got: Unexpected::Str(got), .. }
+                if got == format!("{}…", "a".repeat(64))
+        ));
+    }
+
+    #[test]
+    fn it_validates_sequences_with_paths() {
+        let tags = sequence::<String, Vec<_>>(string).non_empty().unique();
+
+        let value: Value =
+            encode::map([("tags", encode::sequence(encode::string, ["a", "b", "a"]))]).into();
+
+        let error = map(value)
+            .and_then(|mut fields| fields.required("tags", |value| tags.run(value)))
+            .expect_err("Tags are not unique");
+
+        assert_eq!(
+            error.path(),
+            [Segment::Key("tags".to_owned()), Segment::Index(2)]
+        );
+        assert!(matches!(error.root(), Error::InvalidValue { .. }));
+
+        assert!(
+            tags.run(encode::sequence(encode::string, Vec::<String>::new()))
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn it_validates_patterns() {
+        let slug = string.matches(regex::Regex::new("^[a-z-]+$").expect("Valid regex"));
+
+        assert!(slug.run(encode::string("hello-world")).is_ok());
+        assert!(matches!(
+            slug.run(encode::string("Hello")),
+            Err(Error::InvalidValue { expected, .. }) if expected == "a string matching `^[a-z-]+$`"
+        ));
+    }
+}
@@ -1,6 +1,8 @@
+mod case;
 mod map;
 
-pub use map::Map;
+pub use case::Case;
+pub use map::{Field, Map};
 
 use crate::Error;
 pub(crate) use decoder_value::Value as Raw;
@@ -8,6 +10,7 @@ pub(crate) use decoder_value::Value as Raw;
 use serde::Serialize;
 use serde::de;
 use serde::ser;
+use std::fmt;
 
 /// A generic value.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -19,6 +22,147 @@ pub(crate) fn to_value(data: impl Serialize) -> Result<Value, Error> {
         .map_err(Error::deserializer)
 }
 
+/// Looks through any options and newtypes wrapping the given raw value.
+pub(crate) fn inner(mut raw: &Raw) -> &Raw {
+    while let Raw::Option(Some(value)) | Raw::Newtype(value) = raw {
+        raw = value;
+    }
+
+    raw
+}
+
+/// Looks through any options and newtypes wrapping the given raw value.
+pub(crate) fn inner_mut(mut raw: &mut Raw) -> &mut Raw {
+    loop {
+        match raw {
+            Raw::Option(Some(value)) | Raw::Newtype(value) => raw = value,
+            _ => return raw,
+        }
+    }
+}
+
+/// Renders a raw scalar value (i.e. a boolean, a number, or a string) as a string.
+pub(crate) fn scalar(raw: &Raw) -> Result<String, Error> {
+    Ok(match *inner(raw) {
+        Raw::Bool(v) => v.to_string(),
+        Raw::U8(v) => v.to_string(),
+        Raw::U16(v) => v.to_string(),
+        Raw::U32(v) => v.to_string(),
+        Raw::U64(v) => v.to_string(),
+        Raw::I8(v) => v.to_string(),
+        Raw::I16(v) => v.to_string(),
+        Raw::I32(v) => v.to_string(),
+        Raw::I64(v) => v.to_string(),
+        Raw::F32(v) => v.to_string(),
+        Raw::F64(v) => v.to_string(),
+        Raw::Char(v) => v.to_string(),
+        Raw::String(ref v) => v.clone(),
+        ref value => {
+            return Err(Error::InvalidType {
+                expected: "string, number, or boolean".into(),
+                got: value.unexpected(),
+            });
+        }
+    })
+}
+
+/// Whether the given raw value is null (i.e. a unit or an empty option).
+pub(crate) fn is_null(raw: &Raw) -> bool {
+    matches!(inner(raw), Raw::Unit | Raw::Option(None))
+}
+
+/// A number of a raw value; integers are kept exact.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Number {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Number {
+    pub(crate) fn as_f64(self) -> f64 {
+        match self {
+            Number::Integer(n) => n as f64,
+            Number::Float(n) => n,
+        }
+    }
+
+    pub(crate) fn is_integer(self) -> bool {
+        match self {
+            Number::Integer(_) => true,
+            Number::Float(n) => n.fract() == 0.0,
+        }
+    }
+}
+
+/// Integers are compared exactly, and only compared as floats with floats.
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(std::cmp::Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (*self, *other) {
+            (Number::Integer(a), Number::Integer(b)) => Some(a.cmp(&b)),
+            (a, b) => a.as_f64().partial_cmp(&b.as_f64()),
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Integer(n) => n.fmt(f),
+            Number::Float(n) => n.fmt(f),
+        }
+    }
+}
+
+pub(crate) fn number(raw: &Raw) -> Option<Number> {
+    Some(match *raw {
+        Raw::U8(n) => Number::Integer(n.into()),
+        Raw::U16(n) => Number::Integer(n.into()),
+        Raw::U32(n) => Number::Integer(n.into()),
+        Raw::U64(n) => Number::Integer(n.into()),
+        Raw::I8(n) => Number::Integer(n.into()),
+        Raw::I16(n) => Number::Integer(n.into()),
+        Raw::I32(n) => Number::Integer(n.into()),
+        Raw::I64(n) => Number::Integer(n.into()),
+        Raw::F32(n) => Number::Float(n.into()),
+        Raw::F64(n) => Number::Float(n),
+        _ => return None,
+    })
+}
+
+/// Compares two values following the equality rules of JSON Patch and JSON Schema;
+/// numbers are compared by value and maps regardless of the order of their keys.
+pub(crate) fn equal(a: &Raw, b: &Raw) -> bool {
+    let (a, b) = (inner(a), inner(b));
+
+    if is_null(a) && is_null(b) {
+        return true;
+    }
+
+    match (a, b) {
+        (Raw::Map(a), Raw::Map(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| equal(a, b)))
+        }
+        (Raw::Seq(a), Raw::Seq(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b))
+        }
+        (Raw::Char(a), Raw::String(b)) | (Raw::String(b), Raw::Char(a)) => {
+            b.chars().eq(std::iter::once(*a))
+        }
+        _ => match (number(a), number(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        },
+    }
+}
+
 impl From<Raw> for Value {
     fn from(value: Raw) -> Self {
         Self(value)
@@ -0,0 +1,124 @@
+/// A case convention for the keys of a [`Map`](crate::Map).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// `snake_case`
+    Snake,
+    /// `camelCase`
+    Camel,
+    /// `kebab-case`
+    Kebab,
+    /// `PascalCase`
+    Pascal,
+}
+
+impl Case {
+    /// Converts the given name to the [`Case`] convention.
+    ///
+    /// Words are split on `_`, `-`, spaces, and changes of case (e.g. `HTTPServer` is
+    /// split into `HTTP` and `Server`).
+    pub fn apply(self, name: &str) -> String {
+        let words = words(name);
+
+        match self {
+            Self::Snake => lowercase(&words).join("_"),
+            Self::Kebab => lowercase(&words).join("-"),
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            Self::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+}
+
+fn words(name: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (i, &(index, c)) in chars.iter().enumerate() {
+        if matches!(c, '_' | '-' | ' ') {
+            if let Some(start) = start.take() {
+                words.push(&name[start..index]);
+            }
+
+            continue;
+        }
+
+        let Some(begin) = start else {
+            start = Some(index);
+            continue;
+        };
+
+        let previous = chars[i - 1].1;
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+
+        let boundary = c.is_uppercase()
+            && (previous.is_lowercase()
+                || previous.is_numeric()
+                || (previous.is_uppercase() && next.is_some_and(char::is_lowercase)));
+
+        if boundary {
+            words.push(&name[begin..index]);
+            start = Some(index);
+        }
+    }
+
+    if let Some(start) = start {
+        words.push(&name[start..]);
+    }
+
+    words
+}
+
+fn lowercase(words: &[&str]) -> Vec<String> {
+    words.iter().map(|word| word.to_lowercase()).collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    chars
+        .next()
+        .map(|first| {
+            first
+                .to_uppercase()
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_names() {
+        for name in [
+            "user_name",
+            "userName",
+            "UserName",
+            "user-name",
+            "USER_NAME",
+        ] {
+            assert_eq!(Case::Snake.apply(name), "user_name");
+            assert_eq!(Case::Camel.apply(name), "userName");
+            assert_eq!(Case::Kebab.apply(name), "user-name");
+            assert_eq!(Case::Pascal.apply(name), "UserName");
+        }
+
+        assert_eq!(
+            Case::Snake.apply("HTTPServer2Config"),
+            "http_server2_config"
+        );
+        assert_eq!(Case::Camel.apply("max_retries"), "maxRetries");
+    }
+}
@@ -1,12 +1,15 @@
-use crate::{Decoder, Error, Result, Value};
+use super::is_null;
+use crate::{Case, Decoder, Error, Result, Value};
 
 use decoder_value::Value as Raw;
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 
 /// A map of fields and their values, sorted by order of insertion.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Map {
     pub(crate) raw: IndexMap<Raw, Raw>,
+    requested: Option<IndexSet<String>>,
+    case: Option<Case>,
 }
 
 impl Default for Map {
@@ -15,11 +18,58 @@ impl Default for Map {
     }
 }
 
+/// A field of a [`Map`] that distinguishes between a missing and a null value.
+///
+/// See [`Map::nullable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Field<T> {
+    /// The field was not present.
+    #[default]
+    Missing,
+    /// The field was present and explicitly null.
+    Null,
+    /// The field was present with a value.
+    Present(T),
+}
+
+impl<T> Field<T> {
+    /// Returns the value of the [`Field`], if present.
+    pub fn present(self) -> Option<T> {
+        match self {
+            Self::Present(value) => Some(value),
+            Self::Missing | Self::Null => None,
+        }
+    }
+
+    /// Maps the value of the [`Field`], if present.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Field<U> {
+        match self {
+            Self::Missing => Field::Missing,
+            Self::Null => Field::Null,
+            Self::Present(value) => Field::Present(f(value)),
+        }
+    }
+}
+
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Eq for Map {}
+
 impl Map {
     /// Creates a new empty [`Map`].
     pub fn new() -> Self {
+        Self::from_raw(IndexMap::new())
+    }
+
+    pub(crate) fn from_raw(raw: IndexMap<Raw, Raw>) -> Self {
         Self {
-            raw: IndexMap::new(),
+            raw,
+            requested: None,
+            case: None,
         }
     }
 
@@ -27,24 +77,156 @@ impl Map {
     pub fn required<T>(&mut self, key: &str, decoder: impl Decoder<Output = T>) -> Result<T> {
         let value = self.get(key)?;
 
-        decoder.run(value)
+        decoder.run(value).map_err(|error| error.field(key))
+    }
+
+    /// Decodes the given field of the [`Map`] using the given [`Decoder`], or
+    /// returns the given default if it is missing or null.
+    pub fn with_default<T>(
+        &mut self,
+        key: &str,
+        decoder: impl Decoder<Output = T>,
+        default: T,
+    ) -> Result<T> {
+        Ok(self.optional(key, decoder)?.unwrap_or(default))
+    }
+
+    /// Decodes the given field of the [`Map`] using the given [`Decoder`], or
+    /// returns [`Default::default`] if it is missing or null.
+    pub fn or_default<T: Default>(
+        &mut self,
+        key: &str,
+        decoder: impl Decoder<Output = T>,
+    ) -> Result<T> {
+        Ok(self.optional(key, decoder)?.unwrap_or_default())
+    }
+
+    /// Decodes the given field of the [`Map`] using the given [`Decoder`],
+    /// distinguishing between a missing and a null field.
+    ///
+    /// This is useful to model `PATCH` semantics, where a missing field is left
+    /// untouched and a null field is cleared.
+    pub fn nullable<T>(
+        &mut self,
+        key: &str,
+        decoder: impl Decoder<Output = T>,
+    ) -> Result<Field<T>> {
+        let Some(value) = self.take(key) else {
+            return Ok(Field::Missing);
+        };
+
+        if is_null(&value.0) {
+            return Ok(Field::Null);
+        }
+
+        decoder
+            .run(value)
+            .map(Field::Present)
+            .map_err(|error| error.field(key))
+    }
+
+    /// Decodes the first present field among the given aliases using the given
+    /// [`Decoder`].
+    ///
+    /// If none is present, the [`Error`] mentions every alias.
+    pub fn required_any<T>(
+        &mut self,
+        aliases: &[&str],
+        decoder: impl Decoder<Output = T>,
+    ) -> Result<T> {
+        let Some((key, value)) = self.take_any(aliases) else {
+            return Err(self.missing(aliases));
+        };
+
+        decoder.run(value).map_err(|error| error.field(key))
+    }
+
+    /// Decodes the first present field among the given aliases using the given
+    /// [`Decoder`], if any. A null field counts as missing.
+    pub fn optional_any<T>(
+        &mut self,
+        aliases: &[&str],
+        decoder: impl Decoder<Output = T>,
+    ) -> Result<Option<T>> {
+        let Some((key, value)) = self.take_any(aliases) else {
+            return Ok(None);
+        };
+
+        if is_null(&value.0) {
+            return Ok(None);
+        }
+
+        decoder
+            .run(value)
+            .map(Some)
+            .map_err(|error| error.field(key))
     }
 
     /// Decodes the given field of the [`Map`] using the given [`Decoder`], if present.
+    ///
+    /// A null field counts as missing; see [`Map::nullable`] to tell them apart.
     pub fn optional<T>(
         &mut self,
         key: &str,
         decoder: impl Decoder<Output = T>,
     ) -> Result<Option<T>> {
-        let Ok(value) = self.get(key) else {
+        let Some(value) = self.take(key) else {
             return Ok(None);
         };
 
-        if let Raw::Unit = value.0 {
+        if is_null(&value.0) {
             return Ok(None);
         }
 
-        decoder.run(value).map(Some)
+        decoder
+            .run(value)
+            .map(Some)
+            .map_err(|error| error.field(key))
+    }
+
+    /// Makes the [`Map`] keep track of the fields that are requested, so that
+    /// [`Map::finish`] can list them and suggest the closest one.
+    ///
+    /// [`decode::strict`](crate::decode::strict) enables this automatically.
+    pub fn strict(mut self) -> Self {
+        self.requested = Some(IndexSet::new());
+        self
+    }
+
+    /// Ensures that every field of the [`Map`] has been decoded.
+    ///
+    /// Fails with [`Error::UnknownField`] on the first remaining field. If the
+    /// [`Map`] is [`strict`](Self::strict), the error also lists the fields that
+    /// were requested and suggests the closest one, if any.
+    pub fn finish(self) -> Result<()> {
+        let Some(field) = self.raw.keys().next() else {
+            return Ok(());
+        };
+
+        let field = crate::value::scalar(field).unwrap_or_else(|_| format!("{field:?}"));
+
+        Err(Error::unknown_field_in(
+            field,
+            self.requested.into_iter().flatten().collect(),
+        ))
+    }
+
+    /// Normalizes the keys of the [`Map`] to the given [`Case`] convention.
+    ///
+    /// Existing keys are converted, which also affects encoding, and any key
+    /// looked up afterwards is converted the same way before the lookup. If
+    /// multiple keys end up with the same name, the last one is kept.
+    pub fn case(mut self, case: Case) -> Self {
+        self.raw = self
+            .raw
+            .into_iter()
+            .map(|(key, value)| match key {
+                Raw::String(key) => (Raw::String(case.apply(&key)), value),
+                key => (key, value),
+            })
+            .collect();
+        self.case = Some(case);
+        self
     }
 
     /// Inserts a field in the [`Map`] before all the other fields.
@@ -67,27 +249,50 @@ impl Map {
     }
 
     fn get(&mut self, key: &str) -> Result<Value> {
-        self.raw
-            .shift_remove(&Raw::String(key.to_owned()))
-            .ok_or_else(|| Error::FieldMissing {
-                key: key.to_owned(),
-                map: self
-                    .raw
-                    .iter()
-                    .map(|(key, value)| (Value::from(key.clone()), Value::from(value.clone())))
-                    .collect(),
+        self.take(key).ok_or_else(|| self.missing(&[key]))
+    }
+
+    fn missing(&self, keys: &[&str]) -> Error {
+        let available = self
+            .raw
+            .keys()
+            .filter_map(|key| match key {
+                Raw::String(key) => Some(key.clone()),
+                _ => None,
             })
-            .map(Value::from)
+            .collect();
+
+        Error::missing_field(keys, available)
+    }
+
+    fn take_any<'a>(&mut self, aliases: &[&'a str]) -> Option<(&'a str, Value)> {
+        aliases
+            .iter()
+            .find_map(|&alias| Some((alias, self.take(alias)?)))
+    }
+
+    fn take(&mut self, key: &str) -> Option<Value> {
+        if let Some(requested) = &mut self.requested
+            && !requested.contains(key)
+        {
+            let _ = requested.insert(key.to_owned());
+        }
+
+        let key = match self.case {
+            Some(case) => case.apply(key),
+            None => key.to_owned(),
+        };
+
+        self.raw.shift_remove(&Raw::String(key)).map(Value::from)
     }
 }
 
 impl FromIterator<(Value, Value)> for Map {
     fn from_iter<T: IntoIterator<Item = (Value, Value)>>(iter: T) -> Self {
-        Self {
-            raw: iter
-                .into_iter()
+        Self::from_raw(
+            iter.into_iter()
                 .map(|(key, value)| (key.0, value.0))
                 .collect(),
-        }
+        )
     }
 }
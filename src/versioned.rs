@@ -0,0 +1,257 @@
+//! Decode payloads whose shape evolves across versions.
+//!
+//! A [`Versioned`] decoder reads the version of a payload from one of its fields,
+//! runs every migration needed to bring it up to date, and then decodes it:
+//!
+//! ```rust
+//! use decoder::{Decoder, Result, Value};
+//! use decoder::versioned::Versioned;
+//!
+//! struct Person {
+//!     first_name: String,
+//!     last_name: String,
+//! }
+//!
+//! impl Person {
+//!     fn versioned() -> Versioned<impl Decoder<Output = Self>> {
+//!         Versioned::new("version", Person::decode)
+//!             // Version 1 had a single `name` field
+//!             .migration(|value| {
+//!                 use decoder::decode::{map, string};
+//!                 use decoder::encode;
+//!
+//!                 let mut person = map(value)?;
+//!                 let name = person.required("name", string)?;
+//!                 let (first, last) = name.split_once(' ').unwrap_or((&name, ""));
+//!
+//!                 Ok(person
+//!                     .extend(encode::map([
+//!                         ("first_name", encode::string(first)),
+//!                         ("last_name", encode::string(last)),
+//!                     ]))
+//!                     .into())
+//!             })
+//!     }
+//!
+//!     fn decode(value: Value) -> Result<Self> {
+//!         use decoder::decode::{map, string};
+//!
+//!         let mut person = map(value)?;
+//!
+//!         Ok(Self {
+//!             first_name: person.required("first_name", string)?,
+//!             last_name: person.required("last_name", string)?,
+//!         })
+//!     }
+//!
+//!     fn encode(&self) -> Value {
+//!         use decoder::encode::{map, string};
+//!
+//!         Self::versioned()
+//!             .tag(map([
+//!                 ("first_name", string(&self.first_name)),
+//!                 ("last_name", string(&self.last_name)),
+//!             ]))
+//!             .into()
+//!     }
+//! }
+//!
+//! let person = decoder::run(
+//!     serde_json::from_str,
+//!     Person::versioned(),
+//!     r#"{ "version": 1, "name": "Ada Lovelace" }"#,
+//! )
+//! .expect("Decode person");
+//!
+//! assert_eq!(person.last_name, "Lovelace");
+//! assert_eq!(
+//!     serde_json::to_string(&person.encode()).unwrap(),
+//!     r#"{"version":"2","first_name":"Ada","last_name":"Lovelace"}"#
+//! );
+//! ```
+use crate::decode::{lenient, map, u32};
+use crate::{Decoder, Error, Map, Result, Unexpected, Value};
+
+use std::fmt;
+use std::sync::Arc;
+
+/// A [`Decoder`] of versioned payloads.
+///
+/// Versions start at 1, and every [`migration`](Self::migration) bumps the
+/// current version by one.
+pub struct Versioned<D> {
+    key: String,
+    migrations: Vec<Arc<dyn Fn(Value) -> Result<Value> + Send + Sync>>,
+    decoder: D,
+}
+
+impl<D: Decoder> Versioned<D> {
+    /// Creates a new [`Versioned`] decoder that reads the version from the given
+    /// field and decodes the current version with the given [`Decoder`].
+    pub fn new(key: &str, decoder: D) -> Self {
+        Self {
+            key: key.to_owned(),
+            migrations: Vec::new(),
+            decoder,
+        }
+    }
+
+    /// Adds a migration from the current version to the next one.
+    ///
+    /// The migration receives the payload without its version field.
+    pub fn migration(
+        mut self,
+        migration: impl Fn(Value) -> Result<Value> + Send + Sync + 'static,
+    ) -> Self {
+        self.migrations.push(Arc::new(migration));
+        self
+    }
+
+    /// Returns the current version.
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+
+    /// Stamps the current version in the given [`Map`].
+    pub fn tag(&self, map: Map) -> Map {
+        map.tag(&self.key, self.version().to_string())
+    }
+}
+
+impl<D: Decoder> Decoder for Versioned<D> {
+    type Output = D::Output;
+
+    fn run(&self, value: Value) -> Result<D::Output> {
+        let mut payload = map(value)?;
+        let version = payload.required(&self.key, lenient(u32))?;
+
+        if version == 0 || version > self.version() {
+            return Err(Error::OutOfRange {
+                expected: format!("a version between 1 and {}", self.version()).into(),
+                got: Unexpected::Unsigned(version.into()),
+            }
+            .field(&self.key));
+        }
+
+        let value = self.migrations[version as usize - 1..]
+            .iter()
+            .zip(version..)
+            .try_fold(payload.into_value(), |value, (migration, from)| {
+                migration(value).map_err(|error| {
+                    error.origin(format!("migration from version {from} to {}", from + 1))
+                })
+            })?;
+
+        self.decoder.run(value)
+    }
+}
+
+impl<D: Clone> Clone for Versioned<D> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            migrations: self.migrations.clone(),
+            decoder: self.decoder.clone(),
+        }
+    }
+}
+
+impl<D> fmt::Debug for Versioned<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Versioned")
+            .field("key", &self.key)
+            .field("migrations", &self.migrations.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{string, u16};
+    use crate::encode;
+
+    fn server() -> Versioned<impl Decoder<Output = (String, u16)> + Clone + Send + Sync> {
+        Versioned::new("version", |value| {
+            let mut server = map(value)?;
+
+            Ok((
+                server.required("host", string)?,
+                server.required("port", u16)?,
+            ))
+        })
+        // Version 1 called the host `address`
+        .migration(|value| {
+            let mut server = map(value)?;
+            let address = server.required("address", string)?;
+
+            Ok(server
+                .extend(encode::map([("host", encode::string(address))]))
+                .into())
+        })
+        // Version 2 had no port
+        .migration(|value| {
+            Ok(map(value)?
+                .extend(encode::map([("port", encode::u16(80))]))
+                .into())
+        })
+    }
+
+    fn json(input: &str) -> Value {
+        serde_json::from_str(input).expect("Deserialize JSON")
+    }
+
+    #[test]
+    fn it_migrates_payloads() {
+        let server = server();
+        assert_eq!(server.version(), 3);
+
+        for input in [
+            r#"{ "version": 1, "address": "localhost" }"#,
+            r#"{ "version": "2", "host": "localhost" }"#,
+            r#"{ "version": 3, "host": "localhost", "port": 80 }"#,
+        ] {
+            assert_eq!(
+                server.run(json(input)).expect("Decode server"),
+                ("localhost".to_owned(), 80)
+            );
+        }
+
+        let tagged: Value = server.tag(encode::map([])).into();
+        assert_eq!(
+            serde_json::to_string(&tagged).expect("Serialize JSON"),
+            r#"{"version":"3"}"#
+        );
+    }
+
+    #[test]
+    fn it_is_shareable_across_threads() {
+        let server = server();
+        let cloned = server.clone();
+
+        let decoded = std::thread::spawn(move || {
+            cloned.run(json(r#"{ "version": 2, "host": "localhost" }"#))
+        })
+        .join()
+        .expect("Join thread");
+
+        assert_eq!(decoded.expect("Decode server").1, 80);
+        assert_eq!(server.version(), 3);
+    }
+
+    #[test]
+    fn it_reports_failing_migrations() {
+        let server = server();
+
+        let error = server
+            .run(json(r#"{ "version": 1, "host": "localhost" }"#))
+            .expect_err("Address is missing");
+        assert_eq!(error.origin_of(), Some("migration from version 1 to 2"));
+        assert_eq!(error.code(), "missing_field");
+
+        let error = server
+            .run(json(r#"{ "version": 4 }"#))
+            .expect_err("Version is unknown");
+        assert_eq!(error.code(), "out_of_range");
+    }
+}
@@ -0,0 +1,63 @@
+//! Generates arbitrary [`Value`]s out of raw bytes for the fuzz targets.
+//!
+//! `decoder::testing` generates the same trees with a `proptest` strategy instead.
+//! Fuzzers need a generator driven by the bytes they mutate, while `proptest`
+//! shrinks failing trees node by node, which a byte-driven generator cannot do.
+use crate::Value;
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+const MAX_DEPTH: usize = 4;
+const MAX_LEN: usize = 8;
+
+impl<'a> Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        value(u, 0)
+    }
+}
+
+fn value(u: &mut Unstructured<'_>, depth: usize) -> Result<Value> {
+    let variants = if depth < MAX_DEPTH { 19 } else { 15 };
+
+    Ok(match u.choose_index(variants)? {
+        0 => Value::Bool(u.arbitrary()?),
+        1 => Value::U8(u.arbitrary()?),
+        2 => Value::U16(u.arbitrary()?),
+        3 => Value::U32(u.arbitrary()?),
+        4 => Value::U64(u.arbitrary()?),
+        5 => Value::I8(u.arbitrary()?),
+        6 => Value::I16(u.arbitrary()?),
+        7 => Value::I32(u.arbitrary()?),
+        8 => Value::I64(u.arbitrary()?),
+        9 => Value::F32(u.arbitrary()?),
+        10 => Value::F64(u.arbitrary()?),
+        11 => Value::Char(u.arbitrary()?),
+        12 => Value::String(u.arbitrary()?),
+        13 => Value::Unit,
+        14 => Value::Bytes(u.arbitrary()?),
+        15 => Value::Option(if u.arbitrary()? {
+            Some(Box::new(value(u, depth + 1)?))
+        } else {
+            None
+        }),
+        16 => Value::Newtype(Box::new(value(u, depth + 1)?)),
+        17 => {
+            let len = u.int_in_range(0..=MAX_LEN)?;
+
+            Value::Seq(
+                (0..len)
+                    .map(|_| value(u, depth + 1))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        _ => {
+            let len = u.int_in_range(0..=MAX_LEN)?;
+
+            Value::Map(
+                (0..len)
+                    .map(|_| Ok((value(u, depth + 1)?, value(u, depth + 1)?)))
+                    .collect::<Result<_>>()?,
+            )
+        }
+    })
+}
@@ -139,7 +139,7 @@ impl DeserializerError {
             DeserializerError::UnknownVariant(ref field, exp) => E::unknown_variant(field, exp),
             DeserializerError::UnknownField(ref field, exp) => E::unknown_field(field, exp),
             DeserializerError::MissingField(field) => E::missing_field(field),
-            DeserializerError::DuplicateField(field) => E::missing_field(field),
+            DeserializerError::DuplicateField(field) => E::duplicate_field(field),
         }
     }
 
@@ -366,13 +366,18 @@ where
             Value::Option(None) => visitor.visit_none(),
             Value::Option(Some(v)) => visitor.visit_some(ValueDeserializer::new(*v)),
             Value::Newtype(v) => visitor.visit_newtype_struct(ValueDeserializer::new(*v)),
-            Value::Seq(v) => visitor.visit_seq(de::value::SeqDeserializer::new(
-                v.into_iter().map(ValueDeserializer::new),
-            )),
-            Value::Map(v) => visitor
-                .visit_map(de::value::MapDeserializer::new(v.into_iter().map(
-                    |(k, v)| (ValueDeserializer::new(k), ValueDeserializer::new(v)),
-                ))),
+            // The `serde` deserializers fail if the visitor leaves any element behind
+            Value::Seq(v) => de::Deserializer::deserialize_any(
+                de::value::SeqDeserializer::new(v.into_iter().map(ValueDeserializer::new)),
+                visitor,
+            ),
+            Value::Map(v) => de::Deserializer::deserialize_any(
+                de::value::MapDeserializer::new(
+                    v.into_iter()
+                        .map(|(k, v)| (ValueDeserializer::new(k), ValueDeserializer::new(v))),
+                ),
+                visitor,
+            ),
             Value::Bytes(v) => visitor.visit_byte_buf(v),
         }
     }
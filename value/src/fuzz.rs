@@ -0,0 +1,98 @@
+//! Checks shared by the fuzz targets and the corpus tests.
+use crate::{Value, to_value};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+/// Checks that serializing and deserializing the given value produce the value.
+pub fn round_trip(value: &Value) -> Result<(), String> {
+    let serialized = to_value(value).map_err(|error| format!("serializing: {error}"))?;
+
+    if serialized != *value {
+        return Err(format!("serializing produced {serialized:?}"));
+    }
+
+    let deserialized =
+        Value::deserialize(value.clone()).map_err(|error| format!("deserializing: {error}"))?;
+
+    if deserialized != *value {
+        return Err(format!("deserializing produced {deserialized:?}"));
+    }
+
+    Ok(())
+}
+
+/// Deserializes the given value into some typed targets, checking that every
+/// target that succeeds round trips and keeps the items of sequences.
+pub fn deserialize(value: &Value) -> Result<(), String> {
+    typed::<Shape>(value)?;
+    typed::<Wrapper>(value)?;
+    typed::<Record>(value)?;
+    typed::<Option<Vec<String>>>(value)?;
+    typed::<BTreeMap<String, Option<u8>>>(value)?;
+    typed::<(bool, char, f32)>(value)?;
+    typed::<()>(value)
+}
+
+/// Values are compared once serialized, where floats are equal to themselves.
+fn typed<T: Serialize + DeserializeOwned>(value: &Value) -> Result<(), String> {
+    let Ok(target) = T::deserialize(value.clone()) else {
+        return Ok(());
+    };
+
+    let name = std::any::type_name::<T>();
+
+    let serialized = to_value(&target).map_err(|error| format!("serializing {name}: {error}"))?;
+    let deserialized = T::deserialize(serialized.clone())
+        .map_err(|error| format!("deserializing serialized {name}: {error}"))?;
+    let reserialized =
+        to_value(&deserialized).map_err(|error| format!("serializing {name}: {error}"))?;
+
+    if reserialized != serialized {
+        return Err(format!(
+            "{name} does not round trip: {serialized:?} became {reserialized:?}"
+        ));
+    }
+
+    if let (Some(expected), Some(got)) = (items(value), items(&serialized))
+        && expected != got
+    {
+        return Err(format!(
+            "{name} dropped items: {value:?} became {serialized:?}"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the amount of items of a sequence, looking through options and newtypes.
+fn items(value: &Value) -> Option<usize> {
+    match value {
+        Value::Option(Some(value)) | Value::Newtype(value) => items(value),
+        Value::Seq(items) => Some(items.len()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Unit,
+    Newtype(u32),
+    Tuple(i8, String),
+    Struct { a: Option<bool>, b: Vec<u8> },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Wrapper(pub Shape);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub shapes: Vec<Shape>,
+    pub wrapper: Wrapper,
+    pub unit: (),
+    pub tag: Option<char>,
+    pub pair: (u64, i64),
+    pub scores: BTreeMap<String, f64>,
+}
@@ -1,6 +1,13 @@
 mod de;
 mod ser;
 
+#[cfg(any(test, feature = "arbitrary"))]
+mod arbitrary;
+
+#[cfg(any(test, feature = "fuzzing"))]
+#[doc(hidden)]
+pub mod fuzz;
+
 pub use de::{DeserializerError, Unexpected};
 pub use ser::{SerializerError, to_value};
 
@@ -67,7 +74,7 @@ impl Value {
         self.unexpected_().into()
     }
 
-    fn unexpected_(&self) -> serde::de::Unexpected {
+    fn unexpected_(&self) -> serde::de::Unexpected<'_> {
         match *self {
             Value::Bool(b) => serde::de::Unexpected::Bool(b),
             Value::U8(n) => serde::de::Unexpected::Unsigned(n as u64),
@@ -188,3 +195,101 @@ impl PartialOrd for Value {
         Some(self.cmp(rhs))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz::{Record, Shape, Wrapper};
+
+    use ::arbitrary::{Arbitrary, Unstructured};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::Path;
+
+    /// Returns every `Value` in the corpus shared with the fuzz targets, followed by
+    /// the regressions they found.
+    fn corpus() -> Vec<(String, Value)> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        let entries = ["corpus", "regressions"].into_iter().flat_map(|directory| {
+            let mut entries: Vec<_> = fs::read_dir(root.join(directory))
+                .expect("Read corpus directory")
+                .map(|entry| entry.expect("Read corpus entry").path())
+                .collect();
+
+            entries.sort();
+            entries
+        });
+
+        entries
+            .into_iter()
+            .map(|path| {
+                let bytes = fs::read(&path).expect("Read corpus file");
+                let value = Value::arbitrary(&mut Unstructured::new(&bytes))
+                    .expect("Generate value from corpus file");
+
+                (path.display().to_string(), value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn corpus_round_trips() {
+        let corpus = corpus();
+        assert!(!corpus.is_empty());
+
+        for (path, value) in corpus {
+            if let Err(error) = fuzz::round_trip(&value) {
+                panic!("{path}: {error}");
+            }
+        }
+    }
+
+    #[test]
+    fn corpus_deserializes_into_typed_targets() {
+        for (path, value) in corpus() {
+            if let Err(error) = fuzz::deserialize(&value) {
+                panic!("{path}: {error}");
+            }
+        }
+    }
+
+    #[test]
+    fn regressions_are_checked() {
+        let regressions = corpus()
+            .into_iter()
+            .filter(|(path, _)| path.contains("regressions"))
+            .count();
+
+        assert!(regressions > 0);
+    }
+
+    #[test]
+    fn typed_values_round_trip() {
+        let record = Record {
+            shapes: vec![
+                Shape::Unit,
+                Shape::Newtype(42),
+                Shape::Tuple(-1, "a".to_owned()),
+                Shape::Struct {
+                    a: Some(true),
+                    b: vec![1, 2, 3],
+                },
+                Shape::Struct { a: None, b: vec![] },
+            ],
+            wrapper: Wrapper(Shape::Newtype(7)),
+            unit: (),
+            tag: Some('x'),
+            pair: (u64::MAX, i64::MIN),
+            scores: BTreeMap::from([("a".to_owned(), 1.5), ("b".to_owned(), -0.0)]),
+        };
+
+        let value = to_value(&record).expect("Serialize record");
+        assert_eq!(
+            Record::deserialize(value.clone()).expect("Deserialize record"),
+            record
+        );
+        assert!(fuzz::deserialize(&value).is_ok());
+    }
+}